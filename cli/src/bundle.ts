@@ -22,16 +22,17 @@ const HAZARDS: Record<string, RegExp> = {
 };
 
 /**
- * The generated per-flow entry module. It bakes in the flow's steps and wires
- * its `_ts` functions, exposes `handle(input)` over the WASM envelope contract
- * (see docs/ARTIFACT_SPEC.md), and — under Javy — drives it over stdin/stdout.
+ * The generated per-flow entry module. It bakes in the flow (minus its inline
+ * `examples`, which are test data), wires its `_ts` functions, exposes
+ * `handle(input)` over the WASM envelope contract (see docs/ARTIFACT_SPEC.md),
+ * and — under Javy — drives it over stdin/stdout.
  */
 export function generateEntry(flow: Flow, modules: string[]): string {
   const imports = modules.map((name, i) => `import fn_${i} from './functions/${name}';`).join('\n');
   const registry = modules.map((name, i) => `  ${JSON.stringify(name)}: fn_${i},`).join('\n');
 
   return `// Generated by \`weavster compile\` — do not edit.
-import { applyFlow, json, xml } from '@weavster/core';
+import { applyFlow, json, skipsOutput, xml } from '@weavster/core';
 ${imports}
 
 // QuickJS/Javy has no structuredClone; the canonical model is JSON-shaped, so a
@@ -39,7 +40,7 @@ ${imports}
 globalThis.structuredClone ??= (v) =>
   v === undefined ? undefined : JSON.parse(JSON.stringify(v));
 
-const flow = ${JSON.stringify(flow)};
+const flow = ${JSON.stringify({ ...flow, examples: undefined })};
 const functions = {
 ${registry}
 };
@@ -59,6 +60,7 @@ export function handle(input) {
   } catch (e) {
     return { ok: false, error: { stage: 'transform', type: e.name, message: e.message } };
   }
+  if (skipsOutput(flow, out)) return { ok: true, skipped: true };
   try {
     return { ok: true, payload: serializers[input.out](out) };
   } catch (e) {
@@ -1,7 +1,8 @@
 import { existsSync, statSync } from 'node:fs';
 import { dirname, join } from 'node:path';
 import type { Command } from 'commander';
-import { compile } from '../compile.js';
+import { compile, formatJson, formatReport } from '../compile.js';
+import { progress } from '../progress.js';
 
 /** Resolve a path argument (a project dir or a weavster.yaml file) to the project directory. */
 function resolveProjectDir(path: string): string {
@@ -9,21 +10,47 @@ function resolveProjectDir(path: string): string {
   return path;
 }
 
+interface CompileCommandOptions {
+  out?: string;
+  report?: boolean;
+  test?: boolean;
+  format: string;
+}
+
 export function registerCompile(program: Command): void {
   program
     .command('compile')
     .description('Compile enabled pipelines into a portable artifact (manifest + flow modules)')
     .argument('[path]', 'project directory or weavster.yaml (default: current directory)', '.')
     .option('-o, --out <dir>', 'artifact output directory (default: <project>/target/artifact)')
-    .action(async (path: string, options: { out?: string }) => {
+    .option('--report', 'print per-flow module size, step count, and bundled functions')
+    .option('--test', "run each flow's fixtures through its compiled module")
+    .option('--format <format>', 'output format: text or json (the result on stdout)', 'text')
+    .action(async (path: string, options: CompileCommandOptions) => {
+      if (options.format !== 'text' && options.format !== 'json') {
+        console.error(`✗ unknown format "${options.format}" (expected text or json)`);
+        process.exitCode = 1;
+        return;
+      }
+      const json = options.format === 'json';
       const dir = resolveProjectDir(path);
       const outDir = options.out ?? join(dir, 'target', 'artifact');
-      const result = await compile(dir, outDir);
+      const result = await compile(dir, outDir, {
+        // JSON is for machines: no progress display to interleave with it.
+        progress: json ? undefined : (total) => progress(total, 'compiling'),
+        test: options.test,
+      });
 
+      if (json) {
+        console.log(formatJson(result));
+        if (!result.ok) process.exitCode = 1;
+        return;
+      }
       for (const error of result.errors) console.error(`✗ ${error}`);
       if (result.ok) {
         const count = result.pipelines.length;
         console.error(`✓ compiled ${count} pipeline${count === 1 ? '' : 's'} → ${result.outDir}`);
+        if (options.report) console.error(`\n${formatReport(result.flows)}`);
       } else {
         process.exitCode = 1;
       }
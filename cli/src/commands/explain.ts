@@ -0,0 +1,60 @@
+import type { Command } from 'commander';
+import { type ExplainFormat, diagramFlow, explainFlow } from '@weavster/core';
+import { loadFlow } from '../flow.js';
+import { describeConnector, pipelinesUsing } from '../pipeline.js';
+
+const FORMATS: ExplainFormat[] = ['text', 'markdown'];
+
+export function registerExplain(program: Command): void {
+  program
+    .command('explain')
+    .description('Narrate what a flow does, step by step, in plain language')
+    .argument('<flow>', 'flow name (flows/<flow>.yaml)')
+    .argument('[path]', 'project directory', '.')
+    .option('--format <format>', 'output format: text or markdown', 'text')
+    .option('--diagram', 'draw the flow as an ASCII diagram, source to sink, instead')
+    .action((flowName: string, path: string, options: { format: string; diagram?: boolean }) => {
+      const format = options.format as ExplainFormat;
+      if (!FORMATS.includes(format)) {
+        console.error(`✗ unknown format "${options.format}" (expected text or markdown)`);
+        process.exitCode = 1;
+        return;
+      }
+
+      const { flow, errors } = loadFlow(path, flowName);
+      if (!flow) {
+        console.error(`✗ ${flowName}`);
+        for (const error of errors) console.error(`  ${error}`);
+        process.exitCode = 1;
+        return;
+      }
+
+      const code = format === 'markdown' ? (s: string) => `\`${s}\`` : (s: string) => s;
+      const heading = `Flow ${code(flowName)}`;
+      console.log(format === 'markdown' ? `### ${heading}\n` : `${heading}\n`);
+
+      const pipelines = pipelinesUsing(path, flowName);
+      if (options.diagram) {
+        // The diagram is plain text either way; markdown fences it.
+        const ends = (side: 'source' | 'sink') =>
+          pipelines.map(
+            ([name, pipeline]) => `${describeConnector(pipeline[side])} (pipeline ${name})`,
+          );
+        const diagram = diagramFlow(flow, { inputs: ends('source'), outputs: ends('sink') });
+        console.log(format === 'markdown' ? `\`\`\`text\n${diagram}\n\`\`\`` : diagram);
+        return;
+      }
+
+      for (const [name, pipeline] of pipelines) {
+        console.log(`Reads from ${describeConnector(pipeline.source, code)} (pipeline ${name})`);
+      }
+      if (pipelines.length > 0) console.log('');
+
+      console.log(explainFlow(flow, format));
+
+      if (pipelines.length > 0) console.log('');
+      for (const [name, pipeline] of pipelines) {
+        console.log(`Writes to ${describeConnector(pipeline.sink, code)} (pipeline ${name})`);
+      }
+    });
+}
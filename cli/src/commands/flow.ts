@@ -0,0 +1,108 @@
+import type { Command } from 'commander';
+import { stdoutSink } from '../connectors.js';
+import { copyFlow } from '../flow.js';
+import { pipelinesUsing } from '../pipeline.js';
+import { loadSimSpec, parseDuration, parseRate, simulateFlow } from '../simulate.js';
+
+interface SimulateCommandOptions {
+  spec: string;
+  rate: string;
+  duration: string;
+  seed?: string;
+  withOutputs?: boolean;
+}
+
+const fail = (error: string) => {
+  console.error(`✗ ${error}`);
+  process.exitCode = 1;
+};
+
+export function registerFlow(program: Command): void {
+  const flow = program.command('flow').description('Manage the flows in a project');
+
+  flow
+    .command('copy')
+    .description('Start a new flow from a copy of an existing one')
+    .argument('<src>', 'flow to copy (flows/<src>.yaml)')
+    .argument('<dst>', 'name of the new flow (flows/<dst>.yaml)')
+    .argument('[path]', 'project directory', '.')
+    .action((src: string, dst: string, path: string) => {
+      const result = copyFlow(path, src, dst);
+      if (!result.ok) {
+        console.error(`✗ ${result.error}`);
+        process.exitCode = 1;
+        return;
+      }
+      console.log(`✓ copied ${src} to ${result.file}`);
+
+      // The copy is not wired to any pipeline. The pipelines that feed the
+      // original show which connectors a pipeline for the copy would start from.
+      const feeding = pipelinesUsing(path, src);
+      for (const [name, { source, sink }] of feeding) {
+        const route = `${source.path ?? source.type} → ${sink.path ?? sink.type}`;
+        console.log(`! pipeline ${name} routes ${route} through ${src}`);
+      }
+      if (feeding.length > 0) {
+        console.log(`  add a pipeline for ${dst} in pipelines/ and adjust its source and sink`);
+      }
+    });
+
+  flow
+    .command('simulate')
+    .description('Run synthetic documents through a flow at a steady rate')
+    .argument('<name>', 'flow to load (flows/<name>.yaml)')
+    .argument('[path]', 'project directory', '.')
+    .requiredOption('--spec <file>', 'simulation spec: a generator for each field')
+    .option('--rate <rate>', 'documents per second, e.g. 200/s or 6000/m', '100/s')
+    .option('--duration <duration>', 'how long to run, e.g. 500ms, 60s, or 5m', '10s')
+    .option('--seed <n>', "seed for the generators (overrides the spec's seed)")
+    .option('--with-outputs', 'write each output to stdout as a JSON line instead of discarding it')
+    .action(async (name: string, path: string, options: SimulateCommandOptions) => {
+      const rate = parseRate(options.rate);
+      if (rate === undefined) return fail(`--rate "${options.rate}" is not like 200/s`);
+      const durationMs = parseDuration(options.duration);
+      if (durationMs === undefined) {
+        return fail(`--duration "${options.duration}" is not like 500ms, 60s, or 5m`);
+      }
+      const seed = options.seed === undefined ? undefined : Number(options.seed);
+      if (seed !== undefined && !Number.isInteger(seed)) {
+        return fail(`--seed "${options.seed}" is not an integer`);
+      }
+      const { spec, errors } = loadSimSpec(options.spec);
+      if (spec === null) {
+        console.error(`✗ ${options.spec}`);
+        for (const error of errors) console.error(`  ${error}`);
+        process.exitCode = 1;
+        return;
+      }
+
+      const report = await simulateFlow(path, name, spec, {
+        rate,
+        durationMs,
+        seed,
+        sink: options.withOutputs ? stdoutSink() : undefined,
+      });
+      // The summary goes to stderr so --with-outputs stays pipeable.
+      for (const error of report.errors) console.error(`✗ ${error}`);
+      if (report.errors.length > 0) {
+        process.exitCode = 1;
+        return;
+      }
+      const seconds = report.elapsedMs / 1000;
+      const mark = report.ok ? '✓' : '✗';
+      console.error(`${mark} simulated ${name} for ${seconds.toFixed(1)}s at ${rate}/s`);
+      console.error(
+        `  ${report.generated} generated, ${report.transformed} transformed, ` +
+          `${report.skipped} skipped, ${report.failed} failed`,
+      );
+      const { p50, p99 } = report.latencyMs;
+      console.error(
+        `  ${(report.generated / seconds).toFixed(1)} documents/s; ` +
+          `p50 ${p50.toFixed(2)} ms, p99 ${p99.toFixed(2)} ms per document`,
+      );
+      for (const failure of report.failures) console.error(`  ${failure}`);
+      if (report.samples.length > 0) console.error('  sample outputs:');
+      for (const sample of report.samples) console.error(`    ${sample}`);
+      if (!report.ok) process.exitCode = 1;
+    });
+}
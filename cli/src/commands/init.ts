@@ -1,12 +1,13 @@
 import type { Command } from 'commander';
-import { scaffoldProject } from '../init.js';
+import { initGit, scaffoldProject } from '../init.js';
 
 export function registerInit(program: Command): void {
   program
     .command('init')
     .description('Scaffold a new Weavster project')
     .argument('[dir]', 'target directory', '.')
-    .action((dir: string) => {
+    .option('--git', 'initialize a git repository and commit the scaffold')
+    .action((dir: string, options: { git?: boolean }) => {
       const result = scaffoldProject(dir);
       if (!result.ok) {
         console.error(`✗ ${result.error}`);
@@ -15,6 +16,16 @@ export function registerInit(program: Command): void {
       }
       console.log(`✓ scaffolded a Weavster project in ${dir}`);
       for (const file of result.created) console.log(`  ${file}`);
+      if (options.git) {
+        const repo = initGit(dir);
+        if (repo.ok) {
+          console.log('✓ initialized a git repository with an initial commit');
+        } else if (repo.missing) {
+          console.log('! git is not installed; skipped --git (run `git init` later)');
+        } else {
+          console.error(`! ${repo.error}`);
+        }
+      }
       console.log('\nnext: weavster validate && weavster test');
     });
 }
@@ -0,0 +1,29 @@
+import type { Command } from 'commander';
+import { lintFlow } from '@weavster/core';
+import { loadFlow } from '../flow.js';
+
+export function registerLint(program: Command): void {
+  program
+    .command('lint')
+    .description('Warn about flow steps that are valid but likely not what was meant')
+    .argument('<flow>', 'flow name (flows/<flow>.yaml)')
+    .argument('[path]', 'project directory', '.')
+    .action((flowName: string, path: string) => {
+      const { flow, errors } = loadFlow(path, flowName);
+      if (!flow) {
+        console.error(`✗ ${flowName}`);
+        for (const error of errors) console.error(`  ${error}`);
+        process.exitCode = 1;
+        return;
+      }
+
+      // Warnings are advice, not failures: the flow is valid, so exit 0.
+      const warnings = lintFlow(flow);
+      if (warnings.length === 0) {
+        console.log(`✓ ${flowName}: no warnings`);
+        return;
+      }
+      console.log(`⚠ ${flowName}: ${warnings.length} warning${warnings.length === 1 ? '' : 's'}`);
+      for (const warning of warnings) console.log(`  ${warning}`);
+    });
+}
@@ -1,25 +1,56 @@
 import type { Command } from 'commander';
 import { runPipelines } from '../runner.js';
 
+/** Indent every line of a (possibly multi-line) error under its pipeline. */
+const indent = (text: string) => text.replace(/^/gm, '  ');
+
+interface RunCommandOptions {
+  record?: string;
+  replayFile?: string;
+  prettyErrors?: boolean;
+  afterEach?: string;
+  sink?: boolean;
+}
+
 export function registerRun(program: Command): void {
   program
     .command('run')
     .description('Run pipelines: read a source, transform with a flow, write a sink')
     .argument('[name]', 'pipeline name (default: all pipelines)')
-    .action(async (name: string | undefined) => {
-      const report = await runPipelines('.', name);
+    .option('--record <path>', 'append every source document to a JSON Lines recording')
+    .option('--replay-file <path>', "feed a recording's documents instead of the sources")
+    .option('--pretty-errors', 'show failed documents as diagnostics with the failing step')
+    .option('--after-each <command>', 'shell command to run when a pipeline drains a file')
+    .option('--no-sink', 'transform every document but write nothing (for load testing)')
+    .action(async (name: string | undefined, options: RunCommandOptions) => {
+      const report = await runPipelines('.', name, {
+        record: options.record,
+        replay: options.replayFile,
+        prettyErrors: options.prettyErrors ? { color: Boolean(process.stderr.isTTY) } : undefined,
+        afterEach: options.afterEach,
+        // Commander turns `--no-sink` into `sink: false`.
+        noSink: options.sink === false,
+      });
 
       // Status goes to stderr so a stdout sink stays pipeable.
       for (const error of report.errors) console.error(`✗ ${error}`);
       for (const result of report.results) {
         const docs = `${result.documents} document${result.documents === 1 ? '' : 's'}`;
+        const skipped = result.skipped ? `, ${result.skipped} skipped as empty` : '';
+        const discarded =
+          result.discarded === undefined ? '' : `, ${result.discarded} discarded by --no-sink`;
         if (result.ok) {
-          console.error(`✓ ${result.name} (${docs})`);
+          console.error(`✓ ${result.name} (${docs}${skipped}${discarded})`);
         } else {
           console.error(`✗ ${result.name}`);
-          if (result.error) console.error(`  ${result.error}`);
+          if (result.error) console.error(indent(result.error));
+        }
+        for (const docError of result.docErrors ?? []) console.error(indent(docError));
+        if (result.afterEach) {
+          const { exitCode, error } = result.afterEach;
+          const mark = exitCode === 0 ? '✓' : '✗';
+          console.error(indent(`${mark} --after-each ${error ?? `exited ${exitCode}`}`));
         }
-        for (const docError of result.docErrors ?? []) console.error(`  ${docError}`);
       }
       if (report.results.length > 0) {
         const ran = report.results.filter((r) => r.ok).length;
@@ -0,0 +1,22 @@
+import type { Command } from 'commander';
+import { type CatalogFormat, describeOperators } from '@weavster/core';
+
+const FORMATS: CatalogFormat[] = ['text', 'markdown', 'json'];
+
+export function registerSchema(program: Command): void {
+  const schema = program.command('schema').description('Describe what the flow DSL supports');
+
+  schema
+    .command('operators')
+    .description('List every step and value operator, with its argument shape')
+    .option('--format <format>', 'output format: text, markdown, or json', 'text')
+    .action((options: { format: string }) => {
+      const format = options.format as CatalogFormat;
+      if (!FORMATS.includes(format)) {
+        console.error(`✗ unknown format "${options.format}" (expected text, markdown, or json)`);
+        process.exitCode = 1;
+        return;
+      }
+      console.log(describeOperators(format));
+    });
+}
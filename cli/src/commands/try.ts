@@ -0,0 +1,22 @@
+import { readFileSync } from 'node:fs';
+import type { Command } from 'commander';
+import { trySteps } from '../try.js';
+
+export function registerTry(program: Command): void {
+  program
+    .command('try')
+    .description('Apply transform steps to a sample record and print the result')
+    .option('-s, --steps <yaml>', 'a step, a list of steps, or a flow, as YAML (default: stdin)')
+    .option('-i, --input <json>', 'the sample record, as JSON', '{}')
+    .action((options: { steps?: string; input: string }) => {
+      // fd 0 is stdin: lets a snippet be piped in or typed, ending with Ctrl-D.
+      const steps = options.steps ?? readFileSync(0, 'utf8');
+      const { output, errors } = trySteps(steps, options.input);
+      if (errors.length > 0) {
+        for (const error of errors) console.error(`✗ ${error}`);
+        process.exitCode = 1;
+        return;
+      }
+      console.log(JSON.stringify(output, null, 2));
+    });
+}
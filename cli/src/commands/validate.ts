@@ -1,32 +1,50 @@
-import { dirname } from 'node:path';
 import type { Command } from 'commander';
-import { checkProject } from '../project.js';
-import { checkFlows } from '../flow.js';
-import { checkPipelines } from '../pipeline.js';
+import { type ValidateReport, validateAll, watchProject } from '../validate.js';
+
+interface ValidateCommandOptions {
+  watch?: boolean;
+  full?: boolean;
+}
+
+/** Print a ✓ or ✗ line per file, with the errors of each invalid one. */
+function printReport(report: ValidateReport): void {
+  for (const file of report.files) {
+    if (file.ok) {
+      console.log(`✓ ${file.file} is valid`);
+      continue;
+    }
+    console.error(`✗ ${file.file}`);
+    for (const error of file.errors) console.error(`  ${error}`);
+  }
+}
 
 export function registerValidate(program: Command): void {
   program
     .command('validate')
     .description('Validate a Weavster project config and its flows against the schema')
     .argument('[path]', 'project directory or weavster.yaml path', '.')
-    .action((path: string) => {
-      const result = checkProject(path);
-      if (result.ok) {
-        console.log(`✓ ${result.file} is valid`);
-      } else {
-        console.error(`✗ ${result.file ?? path}`);
-        for (const error of result.errors) console.error(`  ${error}`);
-        process.exitCode = 1;
+    .option('-w, --watch', 're-validate whenever a project file changes')
+    .option('--full', 're-check every file, ignoring the cached results of earlier runs')
+    .action((path: string, options: ValidateCommandOptions) => {
+      if (!options.watch) {
+        const report = validateAll(path, { full: options.full });
+        printReport(report);
+        if (!report.ok) process.exitCode = 1;
+        return;
       }
 
-      const projectDir = result.file ? dirname(result.file) : path;
-      for (const file of [...checkFlows(projectDir), ...checkPipelines(projectDir)]) {
-        if (file.ok) {
-          console.log(`✓ ${file.file} is valid`);
-          continue;
-        }
-        console.error(`✗ ${file.file}`);
-        for (const error of file.errors) console.error(`  ${error}`);
+      try {
+        const onReport = (report: ValidateReport) => {
+          // Clears only on a terminal; piped output keeps every run.
+          console.clear();
+          printReport(report);
+          const invalid = report.files.filter((f) => !f.ok).length;
+          const verdict = invalid === 0 ? '✓ all valid' : `✗ ${invalid} invalid`;
+          console.log(`\n${verdict} — watching for changes (Ctrl-C to stop)`);
+        };
+        watchProject(path, onReport, undefined, { full: options.full });
+      } catch (err) {
+        console.error(`✗ cannot watch ${path}: ${err instanceof Error ? err.message : err}`);
         process.exitCode = 1;
       }
     });
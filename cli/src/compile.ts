@@ -1,31 +1,49 @@
-import { mkdirSync, rmSync, writeFileSync } from 'node:fs';
+import { createHash } from 'node:crypto';
+import { mkdirSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
 import { join } from 'node:path';
+import type { Step } from '@weavster/core';
 import { bundleFlow } from './bundle.js';
+import { checkCompiledFixtures } from './fixtures.js';
+import { loadFlow } from './flow.js';
+import { collectModules } from './functions.js';
 import { javyCompile } from './javy.js';
 import { type Format, extFormat, loadPipeline } from './pipeline.js';
+import { type Progress, silent } from './progress.js';
 import { loadProject } from './project.js';
 import { validateManifest } from './schema.js';
 
 const MANIFEST_VERSION = '1';
 const ABI_VERSION = 'javy-1';
 
-/** A connector in the manifest. Only `file` is supported this phase. */
+/**
+ * A connector in the manifest: `file` (a `glob` to read, a `path` to write) or
+ * `sqlite` (a database `path` and `table`, plus an optional `where` to narrow a
+ * source). A sqlite row is a JSON object, so its `format` is always `json`.
+ */
 interface ManifestSource {
-  type: 'file';
-  glob: string;
+  type: 'file' | 'sqlite';
+  glob?: string;
+  path?: string;
+  table?: string;
+  where?: string;
   format: Format;
 }
 interface ManifestSink {
-  type: 'file';
-  path: string;
+  type: 'file' | 'sqlite';
+  path?: string;
+  table?: string;
   format: Format;
 }
 
+/** Connector types the engine runs; `stdin`/`stdout` exist only under `weavster run`. */
+const ENGINE_CONNECTORS = ['file', 'sqlite'];
+
 export interface ManifestPipeline {
   name: string;
   source: ManifestSource;
   flow: string;
   sink: ManifestSink;
+  rateLimit?: number;
 }
 
 export interface Manifest {
@@ -59,36 +77,51 @@ function toManifestPipeline(
   const { pipeline, errors } = loadPipeline(projectDir, name);
   if (pipeline === null) return { pipeline: null, errors: errors.map((e) => `${name}: ${e}`) };
 
-  if (pipeline.source.type !== 'file' || pipeline.sink.type !== 'file') {
+  const { source, sink } = pipeline;
+  if (!ENGINE_CONNECTORS.includes(source.type) || !ENGINE_CONNECTORS.includes(sink.type)) {
     return {
       pipeline: null,
       errors: [
-        `${name}: compile supports only file connectors (got source "${pipeline.source.type}", sink "${pipeline.sink.type}")`,
+        `${name}: compile supports only file and sqlite connectors (got source "${source.type}", sink "${sink.type}")`,
       ],
     };
   }
-  // The pipeline schema requires `path` on file connectors, so these are present.
-  const sourcePath = pipeline.source.path as string;
-  const sinkPath = pipeline.sink.path as string;
-
-  // The source format must be known to bake it into the manifest; the sink
-  // falls back to the source format, mirroring `weavster run`.
-  const sourceFormat = pipeline.source.format ?? extFormat(sourcePath);
+  // The pipeline schema requires `path` on file and sqlite connectors, so these
+  // are present. The manifest always uses `/`: a path written on Windows must
+  // still resolve when the engine runs the artifact on Linux.
+  const sourcePath = (source.path as string).replaceAll('\\', '/');
+  const sinkPath = (sink.path as string).replaceAll('\\', '/');
+  const sourceFormat = source.type === 'sqlite' ? 'json' : (source.format ?? extFormat(sourcePath));
   if (sourceFormat === undefined) {
     return {
       pipeline: null,
       errors: [`${name}: cannot determine source format for "${sourcePath}" — add a format`],
     };
   }
-  const sinkFormat = pipeline.sink.format ?? extFormat(sinkPath) ?? sourceFormat;
+  const sinkFormat =
+    sink.type === 'sqlite' ? 'json' : (sink.format ?? extFormat(sinkPath) ?? sourceFormat);
 
   return {
     pipeline: {
       name,
-      // A file path is a one-match glob; real glob fan-out is an E4 connector concern.
-      source: { type: 'file', glob: sourcePath, format: sourceFormat },
+      source:
+        source.type === 'sqlite'
+          ? {
+              type: 'sqlite',
+              path: sourcePath,
+              table: source.table,
+              // Omitted rather than undefined: the manifest is schema-checked as-is.
+              ...(source.where === undefined ? {} : { where: source.where }),
+              format: sourceFormat,
+            }
+          : // A file path is a one-match glob; real glob fan-out is an E4 connector concern.
+            { type: 'file', glob: sourcePath, format: sourceFormat },
       flow: pipeline.flow,
-      sink: { type: 'file', path: sinkPath, format: sinkFormat },
+      sink:
+        sink.type === 'sqlite'
+          ? { type: 'sqlite', path: sinkPath, table: sink.table, format: sinkFormat }
+          : { type: 'file', path: sinkPath, format: sinkFormat },
+      ...(pipeline.rateLimit === undefined ? {} : { rateLimit: pipeline.rateLimit }),
     },
     errors: [],
   };
@@ -120,14 +153,88 @@ export function buildManifest(path: string): BuildResult {
   return { manifest, errors: [] };
 }
 
+/** Size and shape of one compiled flow module, for `compile --report` and `--format json`. */
+export interface FlowReport {
+  flow: string;
+  /** The module's path inside the artifact: `flows/<flow>.wasm`. */
+  path: string;
+  /** Hex SHA-256 of the module, to tell whether a rebuild changed it. */
+  sha256: string;
+  /** Size of flows/<flow>.wasm in bytes. */
+  bytes: number;
+  /** Transform steps, counting those nested in `_when`/`_each`. */
+  steps: number;
+  /** `_ts` function modules bundled into the module. */
+  functions: number;
+}
+
 export interface CompileResult {
   ok: boolean;
   outDir: string;
   manifestPath: string | null;
   pipelines: string[];
+  /** One entry per compiled flow, largest module first. Empty when compile fails. */
+  flows: FlowReport[];
   errors: string[];
 }
 
+function countSteps(steps: Step[]): number {
+  let count = 0;
+  for (const step of steps) {
+    count += 1;
+    const arg = Object.values(step)[0] as Record<string, unknown> | null;
+    if (arg === null || typeof arg !== 'object') continue;
+    for (const key of ['then', 'else', 'steps']) {
+      if (Array.isArray(arg[key])) count += countSteps(arg[key] as Step[]);
+    }
+  }
+  return count;
+}
+
+function flowReport(projectDir: string, flowsDir: string, flow: string): FlowReport {
+  const steps = loadFlow(projectDir, flow).flow?.steps ?? [];
+  const wasm = readFileSync(join(flowsDir, `${flow}.wasm`));
+  return {
+    flow,
+    path: `flows/${flow}.wasm`,
+    sha256: createHash('sha256').update(wasm).digest('hex'),
+    bytes: wasm.length,
+    steps: countSteps(steps),
+    functions: collectModules(steps).length,
+  };
+}
+
+/** Render flow reports as an aligned table (`compile --report`). */
+export function formatReport(flows: FlowReport[]): string {
+  const rows = [
+    ['flow', 'size', 'steps', 'functions'],
+    ...flows.map((f) => [
+      f.flow,
+      `${(f.bytes / 1024).toFixed(1)} KiB`,
+      String(f.steps),
+      String(f.functions),
+    ]),
+  ];
+  const widths = rows[0].map((_, col) => Math.max(...rows.map((row) => row[col].length)));
+  return rows
+    .map((row) =>
+      row
+        .map((cell, col) => (col === 0 ? cell.padEnd(widths[col]) : cell.padStart(widths[col])))
+        .join('  '),
+    )
+    .join('\n');
+}
+
+/**
+ * The whole compile outcome as JSON (`compile --format json`), for CI: the
+ * status, where the artifact went, and every flow module in it. Failures carry
+ * their errors and an empty `flows`.
+ */
+export function formatJson(result: CompileResult): string {
+  const { ok, outDir, manifestPath, pipelines, flows, errors } = result;
+  return JSON.stringify({ ok, outDir, manifest: manifestPath, pipelines, flows, errors }, null, 2);
+}
+
 /** Bundle one flow and compile it to flows/<flow>.wasm. Returns any build errors. */
 async function buildFlowWasm(
   projectDir: string,
@@ -150,13 +257,26 @@ async function buildFlowWasm(
   }
 }
 
+export interface CompileOptions {
+  /** Told about each flow as it starts building (`compile` reports nothing without one). */
+  progress?: (total: number) => Progress;
+  /** Run each flow's fixtures through its compiled module, failing on any mismatch. */
+  test?: boolean;
+}
+
 /**
  * Compile a project into an artifact directory: build each flow to wasm and emit
  * manifest.json. Flows shared by multiple pipelines compile once.
  */
-export async function compile(projectDir: string, outDir: string): Promise<CompileResult> {
+export async function compile(
+  projectDir: string,
+  outDir: string,
+  options: CompileOptions = {},
+): Promise<CompileResult> {
   const { manifest, errors } = buildManifest(projectDir);
-  if (manifest === null) return { ok: false, outDir, manifestPath: null, pipelines: [], errors };
+  if (manifest === null) {
+    return { ok: false, outDir, manifestPath: null, pipelines: [], flows: [], errors };
+  }
 
   // Start from a clean flows/ so a disabled or removed pipeline's .wasm from a
   // previous run can't linger beside a manifest that no longer references it.
@@ -166,11 +286,23 @@ export async function compile(projectDir: string, outDir: string): Promise<Compi
 
   const flows = [...new Set(manifest.pipelines.map((p) => p.flow))];
   const buildErrors: string[] = [];
-  for (const flow of flows) {
-    buildErrors.push(...(await buildFlowWasm(projectDir, flowsDir, flow)));
+  const status = options.progress?.(flows.length) ?? silent;
+  try {
+    for (const flow of flows) {
+      status.step(flow);
+      buildErrors.push(...(await buildFlowWasm(projectDir, flowsDir, flow)));
+    }
+  } finally {
+    status.done();
+  }
+  if (options.test && buildErrors.length === 0) {
+    for (const flow of flows) {
+      const wasm = readFileSync(join(flowsDir, `${flow}.wasm`));
+      buildErrors.push(...checkCompiledFixtures(projectDir, flow, wasm));
+    }
   }
   if (buildErrors.length > 0) {
-    return { ok: false, outDir, manifestPath: null, pipelines: [], errors: buildErrors };
+    return { ok: false, outDir, manifestPath: null, pipelines: [], flows: [], errors: buildErrors };
   }
 
   const manifestPath = join(outDir, 'manifest.json');
@@ -181,6 +313,9 @@ export async function compile(projectDir: string, outDir: string): Promise<Compi
     outDir,
     manifestPath,
     pipelines: manifest.pipelines.map((p) => p.name),
+    flows: flows
+      .map((flow) => flowReport(projectDir, flowsDir, flow))
+      .sort((a, b) => b.bytes - a.bytes),
     errors: [],
   };
 }
@@ -0,0 +1,46 @@
+import { TransformError } from '@weavster/core';
+import { stringify } from 'yaml';
+
+/** One document that failed in a flow, as `run --pretty-errors` reports it. */
+export interface DocFailure {
+  flow: string;
+  /** 1-based position of the document in its source. */
+  document: number;
+  error: unknown;
+}
+
+const RED_BOLD = '\x1b[1;31m';
+const BLUE = '\x1b[34m';
+const RESET = '\x1b[0m';
+
+/**
+ * Render a failed document as a multi-line diagnostic: the bare reason, where
+ * it happened (flow, document, and the chain of steps), and the YAML of the
+ * step that failed. ANSI colors only when `color` is set, so piped output
+ * stays plain.
+ */
+export function renderFailure(failure: DocFailure, color: boolean): string {
+  const paint = (code: string, text: string) => (color ? `${code}${text}${RESET}` : text);
+  const { error } = failure;
+  const frames = error instanceof TransformError ? error.frames : [];
+  const reason =
+    error instanceof TransformError
+      ? error.reason
+      : error instanceof Error
+        ? error.message
+        : String(error);
+
+  const lines = [
+    `${paint(RED_BOLD, 'error')}: ${reason}`,
+    `${paint(BLUE, '  -->')} flow ${failure.flow}, document ${failure.document}`,
+  ];
+  const failed = frames.at(-1);
+  if (failed === undefined) return lines.join('\n');
+
+  const chain = frames.map((f) => `step ${f.index} (${f.op})`).join(' › ');
+  const gutter = paint(BLUE, '   |');
+  lines.push(`${paint(BLUE, '   =')} at ${chain}`, gutter);
+  for (const line of stringify(failed.step).trimEnd().split('\n')) lines.push(`${gutter} ${line}`);
+  lines.push(gutter);
+  return lines.join('\n');
+}
@@ -1,15 +1,24 @@
 import { existsSync, readFileSync, readdirSync, statSync } from 'node:fs';
 import { join } from 'node:path';
-import { type Flow, type TransformFn, applyFlow, json, toValue } from '@weavster/core';
-import { loadFlow } from './flow.js';
+import {
+  type Flow,
+  type FlowExample,
+  type TransformFn,
+  applyFlow,
+  json,
+  skipsOutput,
+  toValue,
+} from '@weavster/core';
+import { listFlows, loadFlow } from './flow.js';
 import { loadFunctions } from './functions.js';
+import { type ResultEnvelope, runEnvelope } from './wasmHost.js';
 
 const FIXTURES_DIR = 'fixtures';
 const INPUT_FILE = 'input.json';
 const EXPECTED_FILE = 'expected.json';
 
 export interface FixtureResult {
-  /** `<flow>/<case>` label. */
+  /** `<flow>/<case>` label; an inline example's case is its `name`, else `example-<n>`. */
   name: string;
   ok: boolean;
   /** Readable expected-vs-actual diff, present when a fixture fails on output. */
@@ -38,32 +47,24 @@ const subdirs = (dir: string): string[] =>
     .sort();
 
 /**
- * Run every fixture case. Fixtures are grouped by flow:
+ * Run every fixture case and inline example. Fixtures are grouped by flow:
  * `fixtures/<flow>/<case>/{input,expected}.json`. Each case's input is parsed,
- * run through `flows/<flow>.yaml`, and compared to the expected output.
+ * run through `flows/<flow>.yaml`, and compared to the expected output. A
+ * flow's `examples:` run the same way after its fixture cases.
  */
 export async function runFixtures(path: string): Promise<TestRunResult> {
   const dir = resolveProjectDir(path);
   const fixturesDir = join(dir, FIXTURES_DIR);
-
-  if (!existsSync(fixturesDir)) {
-    return {
-      ok: false,
-      results: [],
-      errors: [`no ${FIXTURES_DIR}/ directory found at ${fixturesDir}`],
-    };
-  }
-
-  const flows = subdirs(fixturesDir);
-  if (flows.length === 0) {
-    return { ok: false, results: [], errors: [`no fixture flows found in ${fixturesDir}`] };
-  }
+  const fixtureFlows = existsSync(fixturesDir) ? subdirs(fixturesDir) : [];
+  const flowNames = [...new Set([...fixtureFlows, ...listFlows(dir)])].sort();
 
   const results: FixtureResult[] = [];
-  for (const flowName of flows) {
+  for (const flowName of flowNames) {
+    const cases = fixtureFlows.includes(flowName) ? subdirs(join(fixturesDir, flowName)) : [];
     const { flow, errors } = loadFlow(dir, flowName);
+    // A flow that won't load only fails the cases written for it; `validate` reports the rest.
+    if (flow === null && cases.length === 0) continue;
     const load = flow === null ? { functions: {}, errors } : await loadFunctions(dir, flow);
-    const cases = subdirs(join(fixturesDir, flowName));
     for (const caseName of cases) {
       const name = `${flowName}/${caseName}`;
       if (flow === null || load.errors.length > 0) {
@@ -72,11 +73,48 @@ export async function runFixtures(path: string): Promise<TestRunResult> {
       }
       results.push(runCase(join(fixturesDir, flowName, caseName), name, flow, load.functions));
     }
+    for (const [i, example] of (flow?.examples ?? []).entries()) {
+      const name = `${flowName}/${example.name ?? `example-${i + 1}`}`;
+      if (load.errors.length > 0) {
+        results.push({ name, ok: false, error: `flow "${flowName}": ${load.errors.join('; ')}` });
+        continue;
+      }
+      results.push(runExample(example, name, flow as Flow, load.functions));
+    }
   }
 
+  if (fixtureFlows.length === 0 && results.length === 0) {
+    const error = existsSync(fixturesDir)
+      ? `no fixture flows found in ${fixturesDir}`
+      : `no ${FIXTURES_DIR}/ directory found at ${fixturesDir}, and no flow has examples`;
+    return { ok: false, results: [], errors: [error] };
+  }
   return { ok: results.every((r) => r.ok), results, errors: [] };
 }
 
+/** Run one inline example, checking its `expect` output or that the output is skipped. */
+function runExample(
+  example: FlowExample,
+  name: string,
+  flow: Flow,
+  functions: Record<string, TransformFn>,
+): FixtureResult {
+  let out: ReturnType<typeof applyFlow>;
+  try {
+    out = applyFlow(json.parse(JSON.stringify(example.input)), flow, { functions });
+  } catch (err) {
+    return { name, ok: false, error: `input: ${String(err)}` };
+  }
+  const actual = toValue(out.root);
+  if (example.expectSkipped) {
+    if (skipsOutput(flow, out)) return { name, ok: true };
+    const got = JSON.stringify(actual);
+    return { name, ok: false, error: `expected the output to be skipped as empty, got ${got}` };
+  }
+  if (deepEqual(actual, example.expect)) return { name, ok: true };
+  return { name, ok: false, diff: diffJson(example.expect, actual) };
+}
+
 function runCase(
   caseDir: string,
   name: string,
@@ -111,6 +149,63 @@ function runCase(
   return { name, ok: false, diff: diffJson(expected, actual) };
 }
 
+/**
+ * Run a flow's fixture cases through its compiled module rather than the
+ * interpreter (`compile --test`), returning one error per case the module gets
+ * wrong. A flow with no fixtures has nothing to check.
+ */
+export function checkCompiledFixtures(
+  projectDir: string,
+  flow: string,
+  wasm: BufferSource,
+): string[] {
+  const flowDir = join(projectDir, FIXTURES_DIR, flow);
+  if (!existsSync(flowDir)) return [];
+
+  const errors: string[] = [];
+  for (const caseName of subdirs(flowDir)) {
+    const name = `${flow}/${caseName}`;
+    const inputPath = join(flowDir, caseName, INPUT_FILE);
+    const expectedPath = join(flowDir, caseName, EXPECTED_FILE);
+    if (!existsSync(inputPath) || !existsSync(expectedPath)) {
+      errors.push(`${name}: needs both ${INPUT_FILE} and ${EXPECTED_FILE}`);
+      continue;
+    }
+    const payload = readFileSync(inputPath, 'utf8');
+    const result = runEnvelope(wasm, { in: 'json', out: 'json', payload });
+    const error = compiledCaseError(name, result, readFileSync(expectedPath, 'utf8'));
+    if (error !== undefined) errors.push(error);
+  }
+  return errors;
+}
+
+/**
+ * Judge one compiled fixture case: the module's result envelope against the
+ * text of its `expected.json`. An output the module skipped as empty is
+ * compared as `{}`, the same output `weavster test` sees from the interpreter.
+ */
+export function compiledCaseError(
+  name: string,
+  result: ResultEnvelope,
+  expectedText: string,
+): string | undefined {
+  if (!result.ok) {
+    const stage = result.error?.stage ?? 'unknown';
+    return `${name}: compiled module failed at ${stage}: ${result.error?.message ?? ''}`;
+  }
+  let expected: unknown;
+  try {
+    expected = JSON.parse(expectedText);
+  } catch (err) {
+    return `${name}: invalid JSON in ${EXPECTED_FILE}: ${String(err)}`;
+  }
+  const actual = result.skipped ? {} : JSON.parse(result.payload as string);
+  if (deepEqual(actual, expected)) return undefined;
+  const diff = diffJson(expected, actual);
+  const what = result.skipped ? 'skipped the output as empty, unlike' : 'disagrees with';
+  return `${name}: compiled module ${what} ${EXPECTED_FILE}\n${diff}`;
+}
+
 function deepEqual(a: unknown, b: unknown): boolean {
   return JSON.stringify(a) === JSON.stringify(b);
 }
@@ -1,10 +1,13 @@
-import { existsSync, readFileSync, readdirSync } from 'node:fs';
+import { copyFileSync, existsSync, readFileSync, readdirSync } from 'node:fs';
 import { join } from 'node:path';
 import { parse, YAMLParseError } from 'yaml';
-import type { Flow } from '@weavster/core';
+import { type Flow, checkOperators, checkPaths } from '@weavster/core';
+import { checkModules } from './functions.js';
 import { validateFlow } from './schema.js';
 
 const FLOWS_DIR = 'flows';
+/** Flow names become wasm filenames in the compiled artifact, so they must be kebab-case. */
+const FLOW_NAME = /^[a-z0-9][a-z0-9-]*$/;
 
 export interface FlowLoad {
   flow: Flow | null;
@@ -17,7 +20,7 @@ export interface FlowCheck {
   errors: string[];
 }
 
-/** Load and schema-validate a flow by name from a project's `flows/` directory. */
+/** Load a flow by name from a project's `flows/` directory, schema- and path-checked. */
 export function loadFlow(projectDir: string, name: string): FlowLoad {
   const file = join(projectDir, FLOWS_DIR, `${name}.yaml`);
   if (!existsSync(file)) return { flow: null, errors: [`no flow "${name}" at ${file}`] };
@@ -30,9 +33,17 @@ export function loadFlow(projectDir: string, name: string): FlowLoad {
     return { flow: null, errors: [`invalid YAML: ${message}`] };
   }
 
+  return checkFlow(data);
+}
+
+/** Schema-, path-, operator-, and module-check parsed flow data (a flow file's contents). */
+export function checkFlow(data: unknown): FlowLoad {
   const { valid, errors } = validateFlow(data);
   if (!valid) return { flow: null, errors };
-  return { flow: data as Flow, errors: [] };
+  const flow = data as Flow;
+  const checkErrors = [...checkPaths(flow), ...checkOperators(flow), ...checkModules(flow)];
+  if (checkErrors.length > 0) return { flow: null, errors: checkErrors };
+  return { flow, errors: [] };
 }
 
 /** List flow names (without extension) under a project's `flows/` directory. */
@@ -52,3 +63,29 @@ export function checkFlows(projectDir: string): FlowCheck[] {
     return { file: `${FLOWS_DIR}/${name}.yaml`, ok: errors.length === 0, errors };
   });
 }
+
+export interface FlowCopy {
+  ok: boolean;
+  /** The new flow file, relative to the project directory. */
+  file?: string;
+  error?: string;
+}
+
+/**
+ * Start a new flow from an existing one: copy `flows/<src>.yaml` to
+ * `flows/<dst>.yaml` byte for byte, comments included. A flow's name is its
+ * filename, so nothing inside the file changes. Refuses to overwrite.
+ */
+export function copyFlow(projectDir: string, src: string, dst: string): FlowCopy {
+  const from = join(projectDir, FLOWS_DIR, `${src}.yaml`);
+  const file = `${FLOWS_DIR}/${dst}.yaml`;
+  if (!existsSync(from)) return { ok: false, error: `no flow "${src}" at ${from}` };
+  if (!FLOW_NAME.test(dst)) {
+    return { ok: false, error: `flow name "${dst}" must be kebab-case (a-z, 0-9, -)` };
+  }
+  if (existsSync(join(projectDir, file))) {
+    return { ok: false, error: `flow "${dst}" already exists at ${file}` };
+  }
+  copyFileSync(from, join(projectDir, file));
+  return { ok: true, file };
+}
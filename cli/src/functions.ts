@@ -5,7 +5,7 @@ import type { Flow, Step, TransformFn } from '@weavster/core';
 
 const FUNCTIONS_DIR = 'functions';
 
-/** Collect the module names referenced by `_ts` steps, recursing into `_when` branches. */
+/** Collect the module names referenced by `_ts` steps, recursing into `_when`/`_each` steps. */
 export function collectModules(steps: Step[]): string[] {
   const names = new Set<string>();
   const walk = (list: Step[]) => {
@@ -17,12 +17,31 @@ export function collectModules(steps: Step[]): string[] {
         if (Array.isArray(when.then)) walk(when.then as Step[]);
         if (Array.isArray(when.else)) walk(when.else as Step[]);
       }
+      const each = step._each as { steps?: unknown } | undefined;
+      if (each && Array.isArray(each.steps)) walk(each.steps as Step[]);
     }
   };
   walk(steps);
   return [...names];
 }
 
+/** A module name: a file under `functions/`, optionally in subfolders, without `..`. */
+const MODULE_NAME = /^[\w-]+(\/[\w-]+)*$/;
+
+/**
+ * Check every `_ts` module name a flow references. A name resolves to
+ * `functions/<name>.ts` and is spliced into the compiled bundle's imports, so
+ * one that could climb out of `functions/` (or out of an import string) is
+ * refused when the flow loads.
+ */
+export function checkModules(flow: Flow): string[] {
+  return collectModules(flow.steps)
+    .filter((name) => !MODULE_NAME.test(name))
+    .map(
+      (name) => `_ts module "${name}" must be a name under functions/ (letters, digits, _, -, /)`,
+    );
+}
+
 export interface FunctionsLoad {
   functions: Record<string, TransformFn>;
   errors: string[];
@@ -5,6 +5,11 @@ import { registerTest } from './commands/test.js';
 import { registerInit } from './commands/init.js';
 import { registerRun } from './commands/run.js';
 import { registerCompile } from './commands/compile.js';
+import { registerExplain } from './commands/explain.js';
+import { registerLint } from './commands/lint.js';
+import { registerTry } from './commands/try.js';
+import { registerFlow } from './commands/flow.js';
+import { registerSchema } from './commands/schema.js';
 
 const program = new Command();
 
@@ -18,5 +23,10 @@ registerValidate(program);
 registerTest(program);
 registerRun(program);
 registerCompile(program);
+registerExplain(program);
+registerLint(program);
+registerTry(program);
+registerFlow(program);
+registerSchema(program);
 
 program.parseAsync();
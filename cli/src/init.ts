@@ -1,3 +1,4 @@
+import { spawnSync } from 'node:child_process';
 import { existsSync, mkdirSync, writeFileSync } from 'node:fs';
 import { basename, dirname, join, resolve } from 'node:path';
 
@@ -19,6 +20,7 @@ function templates(name: string): Record<string, string> {
       '# Your first flow. Steps run top to bottom; this one adds a field.\nsteps:\n  - _set:\n      status: new\n',
     'fixtures/main/basic/input.json': '{\n  "id": "demo-1"\n}\n',
     'fixtures/main/basic/expected.json': '{\n  "id": "demo-1",\n  "status": "new"\n}\n',
+    '.gitignore': '# weavster compile output\ntarget/\n# weavster validate cache\n.weavster/\n',
     'README.md': `# ${name}\n\nA Weavster project.\n\n- \`weavster validate\` — check the config and flows\n- \`weavster test\` — run fixtures through flows\n`,
   };
 }
@@ -44,3 +46,31 @@ export function scaffoldProject(dir: string): ScaffoldResult {
   }
   return { ok: true, created };
 }
+
+export interface GitInitResult {
+  ok: boolean;
+  /** Set when git is not installed; the project is scaffolded without a repo. */
+  missing?: boolean;
+  error?: string;
+}
+
+function git(dir: string, args: string[]): ReturnType<typeof spawnSync> {
+  return spawnSync('git', args, { cwd: dir, encoding: 'utf8' });
+}
+
+/** `git init` the project and commit the scaffolded files as the first commit. */
+export function initGit(dir: string): GitInitResult {
+  for (const args of [['init'], ['add', '-A'], ['commit', '-m', 'Initial Weavster project']]) {
+    const result = git(dir, args);
+    // ENOENT on the first call means there is no git binary on PATH.
+    if (result.error) {
+      const missing = (result.error as NodeJS.ErrnoException).code === 'ENOENT';
+      return { ok: false, missing, error: result.error.message };
+    }
+    if (result.status !== 0) {
+      const detail = String(result.stderr || result.stdout || `exited ${result.status}`).trim();
+      return { ok: false, error: `git ${args[0]} failed: ${detail}` };
+    }
+  }
+  return { ok: true };
+}
@@ -1,5 +1,5 @@
 import { existsSync, readFileSync, readdirSync } from 'node:fs';
-import { extname, join } from 'node:path';
+import { extname, isAbsolute, join } from 'node:path';
 import { parse, YAMLParseError } from 'yaml';
 import {
   type Sink,
@@ -13,11 +13,18 @@ import { validatePipeline } from './schema.js';
 
 const PIPELINES_DIR = 'pipelines';
 
+/** Connector types that can be read from, and those that can be written to. */
+const SOURCE_TYPES = ['file', 'sqlite', 'stdin'];
+const SINK_TYPES = ['file', 'sqlite', 'stdout'];
+
 export type Format = 'json' | 'xml';
 
 interface ConnectorSpec {
   type: string;
   path?: string;
+  /** `sqlite` only: the table to read or write, and a condition narrowing a read. */
+  table?: string;
+  where?: string;
   format?: Format;
 }
 
@@ -25,6 +32,8 @@ export interface Pipeline {
   source: ConnectorSpec;
   flow: string;
   sink: ConnectorSpec;
+  /** Most documents per second; enforced by the engine, ignored by `weavster run`. */
+  rateLimit?: number;
 }
 
 export interface PipelineLoad {
@@ -38,6 +47,53 @@ export interface PipelineCheck {
   errors: string[];
 }
 
+/**
+ * Check that a connector path stays inside the project: no absolute path and
+ * no `..` segment. The same rule the engine applies to manifest paths, so a
+ * pipeline that runs locally also compiles to an artifact the engine accepts.
+ */
+function checkContained(field: string, path: string): string[] {
+  if (isAbsolute(path)) return [`${field} "${path}" must be relative to the project`];
+  if (path.split(/[\\/]/).includes('..')) return [`${field} "${path}" must not contain ".."`];
+  return [];
+}
+
+/** Whether a connector type can be a pipeline's source. */
+export function isInputCapable(type: string): boolean {
+  return SOURCE_TYPES.includes(type);
+}
+
+/** Whether a connector type can be a pipeline's sink. */
+export function isOutputCapable(type: string): boolean {
+  return SINK_TYPES.includes(type);
+}
+
+/** `a, b, or c`, for naming the connectors an end accepts. */
+const anyOf = (types: string[]) => `${types.slice(0, -1).join(', ')}, or ${types.at(-1)}`;
+
+/**
+ * Check that the source can be read from and the sink written to. Runs ahead
+ * of the schema, whose `oneOf` would report a connector on the wrong end as a
+ * pile of mismatched properties rather than naming the actual mistake.
+ */
+function checkDirections(data: unknown): string[] {
+  const typeOf = (end: 'source' | 'sink') => {
+    const spec = (data as Record<string, unknown> | null)?.[end];
+    const type = (spec as Record<string, unknown> | null)?.type;
+    return typeof type === 'string' ? type : undefined;
+  };
+  const errors: string[] = [];
+  const source = typeOf('source');
+  if (source !== undefined && !isInputCapable(source) && isOutputCapable(source)) {
+    errors.push(`source.type "${source}" is a sink; a source is ${anyOf(SOURCE_TYPES)}`);
+  }
+  const sink = typeOf('sink');
+  if (sink !== undefined && !isOutputCapable(sink) && isInputCapable(sink)) {
+    errors.push(`sink.type "${sink}" is a source; a sink is ${anyOf(SINK_TYPES)}`);
+  }
+  return errors;
+}
+
 /** Load and schema-validate a pipeline by name from a project's `pipelines/` directory. */
 export function loadPipeline(projectDir: string, name: string): PipelineLoad {
   const file = join(projectDir, PIPELINES_DIR, `${name}.yaml`);
@@ -51,9 +107,18 @@ export function loadPipeline(projectDir: string, name: string): PipelineLoad {
     return { pipeline: null, errors: [`invalid YAML: ${message}`] };
   }
 
+  const directionErrors = checkDirections(data);
+  if (directionErrors.length > 0) return { pipeline: null, errors: directionErrors };
+
   const { valid, errors } = validatePipeline(data);
   if (!valid) return { pipeline: null, errors };
-  return { pipeline: data as Pipeline, errors: [] };
+  const pipeline = data as Pipeline;
+  const pathErrors = (['source', 'sink'] as const).flatMap((end) => {
+    const path = pipeline[end].path;
+    return path === undefined ? [] : checkContained(`${end}.path`, path);
+  });
+  if (pathErrors.length > 0) return { pipeline: null, errors: pathErrors };
+  return { pipeline, errors: [] };
 }
 
 /** List pipeline names (without extension) under a project's `pipelines/` directory. */
@@ -66,6 +131,30 @@ export function listPipelines(projectDir: string): string[] {
     .sort();
 }
 
+/**
+ * The pipelines that route documents through `flowName`, by name and in name
+ * order. A pipeline that fails to load is left out.
+ */
+export function pipelinesUsing(projectDir: string, flowName: string): [string, Pipeline][] {
+  return listPipelines(projectDir).flatMap((name) => {
+    const { pipeline } = loadPipeline(projectDir, name);
+    return pipeline?.flow === flowName ? [[name, pipeline] as [string, Pipeline]] : [];
+  });
+}
+
+/**
+ * One-line summary of a connector spec: its type plus the settings it carries
+ * (`file in/order.json as xml`). `code` wraps the type and path, e.g. in
+ * Markdown code spans.
+ */
+export function describeConnector(
+  spec: Pipeline['source'],
+  code: (text: string) => string = (text) => text,
+): string {
+  const settings = [spec.path && code(spec.path), spec.format && `as ${spec.format}`];
+  return [code(spec.type), ...settings.filter(Boolean)].join(' ');
+}
+
 /** Schema-validate every pipeline in a project. */
 export function checkPipelines(projectDir: string): PipelineCheck[] {
   return listPipelines(projectDir).map((name) => {
@@ -81,6 +170,10 @@ export function extFormat(path: string): Format | undefined {
   return undefined;
 }
 
+/** `weavster run` has no SQLite driver; the engine's connector reads and writes the table. */
+const SQLITE_ENGINE_ONLY =
+  'the sqlite connector runs in the engine only; compile the project and run the artifact';
+
 /**
  * Resolve a source spec to a connector, the format to parse with, and whether it is bounded.
  * A bounded source (file) yields once; an unbounded source (stdin) streams until end-of-stream.
@@ -92,6 +185,7 @@ export function resolveSource(
   if (spec.type === 'stdin') {
     return { source: stdinSource(), format: spec.format as Format, bounded: false };
   }
+  if (spec.type === 'sqlite') throw new Error(SQLITE_ENGINE_ONLY);
   const path = join(projectDir, spec.path as string);
   const format = spec.format ?? extFormat(spec.path as string);
   if (format === undefined) {
@@ -109,6 +203,7 @@ export function resolveSink(
   if (spec.type === 'stdout') {
     return { sink: stdoutSink(), format: spec.format ?? sourceFormat };
   }
+  if (spec.type === 'sqlite') throw new Error(SQLITE_ENGINE_ONLY);
   const path = join(projectDir, spec.path as string);
   const format = spec.format ?? extFormat(spec.path as string) ?? sourceFormat;
   return { sink: fileSink(path), format };
@@ -0,0 +1,48 @@
+/**
+ * Progress reporting for long-running commands. Commands report through the
+ * `Progress` interface and never touch the terminal themselves, so the same
+ * code runs silently under CI or a pipe and redraws a status line on a TTY.
+ */
+
+export interface Progress {
+  /** Begin the next item (1-based position out of the total), e.g. a flow being compiled. */
+  step(label: string): void;
+  /** Clear whatever the progress display left behind. */
+  done(): void;
+}
+
+/** Reports nothing: the default wherever a caller passes no progress. */
+export const silent: Progress = { step() {}, done() {} };
+
+/** The slice of a stream `progress` writes to (`process.stderr` in the CLI). */
+export interface ProgressStream {
+  isTTY?: boolean;
+  write(chunk: string): unknown;
+}
+
+/**
+ * A progress display over `total` items. On a TTY it redraws one status line
+ * per step (`[2/5] compiling order`) and erases it when done; on anything
+ * else — a pipe, a CI log — it stays silent, so captured output never carries
+ * control characters.
+ */
+export function progress(
+  total: number,
+  verb: string,
+  stream: ProgressStream = process.stderr,
+): Progress {
+  if (!stream.isTTY) return silent;
+  let index = 0;
+  // `\r` returns to column 0 and `ESC[2K` clears the line, so each step
+  // overwrites the last instead of scrolling.
+  const clear = '\r\x1b[2K';
+  return {
+    step(label) {
+      index += 1;
+      stream.write(`${clear}[${index}/${total}] ${verb} ${label}`);
+    },
+    done() {
+      if (index > 0) stream.write(clear);
+    },
+  };
+}
@@ -0,0 +1,49 @@
+import { appendFile, mkdir, readFile } from 'node:fs/promises';
+import { dirname } from 'node:path';
+import type { Format } from './pipeline.js';
+
+/**
+ * One captured source document (`weavster run --record`), stored as a line of
+ * JSON Lines so a recording can be appended to while it is read, grepped, and
+ * trimmed by hand before a replay.
+ */
+export interface Recorded {
+  pipeline: string;
+  /** 1-based position of the document in its pipeline's run. */
+  document: number;
+  /** The format the source text was parsed as. */
+  format: Format;
+  /** ISO-8601 capture time. */
+  recordedAt: string;
+  /** The raw source text, verbatim. */
+  payload: string;
+}
+
+/** Append one document to a recording file, creating it (and its directory) if needed. */
+export async function appendRecord(path: string, record: Recorded): Promise<void> {
+  await mkdir(dirname(path), { recursive: true });
+  await appendFile(path, `${JSON.stringify(record)}\n`);
+}
+
+/** Read a recording back. A malformed line fails the whole read, naming the line. */
+export async function readRecording(path: string): Promise<Recorded[]> {
+  let text: string;
+  try {
+    text = await readFile(path, 'utf8');
+  } catch {
+    throw new Error(`no recording "${path}"`);
+  }
+  return text.split('\n').flatMap((line, index) => {
+    if (line.trim() === '') return [];
+    try {
+      const record = JSON.parse(line) as Recorded;
+      if (typeof record.pipeline !== 'string' || typeof record.payload !== 'string') {
+        throw new Error('needs "pipeline" and "payload" strings');
+      }
+      return [record];
+    } catch (err) {
+      const reason = err instanceof Error ? err.message : String(err);
+      throw new Error(`${path}:${index + 1}: invalid recording line: ${reason}`);
+    }
+  });
+}
@@ -1,10 +1,15 @@
+import { spawnSync } from 'node:child_process';
 import { existsSync, statSync } from 'node:fs';
-import { join } from 'node:path';
-import { applyFlow, json, xml } from '@weavster/core';
+import { join, resolve } from 'node:path';
+import { applyFlow, json, skipsOutput, xml } from '@weavster/core';
+import { renderFailure } from './diagnostic.js';
 import { loadFlow } from './flow.js';
 import { loadFunctions } from './functions.js';
+import { type Recorded, appendRecord, readRecording } from './recording.js';
 import {
   type Format,
+  type Pipeline,
+  extFormat,
   listPipelines,
   loadPipeline,
   resolveSink,
@@ -25,10 +30,23 @@ export interface RunResult {
   ok: boolean;
   /** Number of documents the source yielded. */
   documents: number;
+  /** Documents whose output was empty and dropped under the flow's `onEmptyOutput: skip`. */
+  skipped?: number;
+  /** Outputs produced but not written, under `noSink`. */
+  discarded?: number;
   /** A startup or bounded-source failure that ends the pipeline. */
   error?: string;
   /** Per-document failures on an unbounded source (logged, did not end the pipeline). */
   docErrors?: string[];
+  /** How the `afterEach` command ended, when it ran. */
+  afterEach?: HookResult;
+}
+
+export interface HookResult {
+  /** The command's exit code; `null` when a signal ended it or it never started. */
+  exitCode: number | null;
+  /** Why the command did not exit cleanly, when it did not start or was killed. */
+  error?: string;
 }
 
 export interface RunReport {
@@ -37,27 +55,92 @@ export interface RunReport {
   errors: string[];
 }
 
+export interface RunOptions {
+  /** Append every source document to this JSON Lines recording. */
+  record?: string;
+  /** Feed the documents in this recording instead of each pipeline's source. */
+  replay?: string;
+  /** Report failed documents as multi-line diagnostics, colored when `color` is set. */
+  prettyErrors?: { color: boolean };
+  /** Shell command to run after each pipeline drains a bounded source. */
+  afterEach?: string;
+  /**
+   * Transform and serialize every document but write nothing: for measuring
+   * the pipeline without touching its sink. Outputs are counted as discarded.
+   */
+  noSink?: boolean;
+}
+
 function resolveProjectDir(path: string): string {
   if (existsSync(path) && statSync(path).isFile()) return join(path, '..');
   return path;
 }
 
-/** Run one pipeline, or every pipeline in the project when no name is given. */
-export async function runPipelines(path: string, name?: string): Promise<RunReport> {
+/**
+ * Run one pipeline, or every pipeline in the project when no name is given. A
+ * replay with no name runs the pipelines the recording holds documents for.
+ */
+export async function runPipelines(
+  path: string,
+  name?: string,
+  options: RunOptions = {},
+): Promise<RunReport> {
   const dir = resolveProjectDir(path);
-  const names = name ? [name] : listPipelines(dir);
+
+  let replay: Recorded[] | undefined;
+  if (options.replay !== undefined) {
+    try {
+      replay = await readRecording(options.replay);
+    } catch (err) {
+      return { ok: false, results: [], errors: [message(err)] };
+    }
+  }
+
+  const names = name
+    ? [name]
+    : replay
+      ? [...new Set(replay.map((r) => r.pipeline))]
+      : listPipelines(dir);
   if (names.length === 0) {
-    return { ok: false, results: [], errors: [`no pipelines found in ${join(dir, 'pipelines')}`] };
+    const where = options.replay ?? join(dir, 'pipelines');
+    return { ok: false, results: [], errors: [`no pipelines found in ${where}`] };
   }
 
   const results: RunResult[] = [];
-  for (const pipelineName of names) results.push(await runOne(dir, pipelineName));
-  return { ok: results.every((r) => r.ok), results, errors: [] };
+  for (const pipelineName of names) {
+    const recorded = replay?.filter((r) => r.pipeline === pipelineName);
+    results.push(await runOne(dir, pipelineName, options, recorded));
+  }
+  const ok = results.every((r) => r.ok && (r.afterEach?.exitCode ?? 0) === 0);
+  return { ok, results, errors: [] };
+}
+
+/** A source over recorded documents, in recording order. */
+function replaySource(records: Recorded[]): { documents(): AsyncIterable<string> } {
+  return {
+    async *documents() {
+      for (const record of records) yield record.payload;
+    },
+  };
+}
+
+/**
+ * The format to read a recording without one in: the source's declared or
+ * file-extension format, else JSON (a `sqlite` source records JSON rows).
+ */
+function replayFormat(source: Pipeline['source']): Format {
+  const fromPath = source.path === undefined ? undefined : extFormat(source.path);
+  return source.format ?? fromPath ?? 'json';
 }
 
 const message = (err: unknown): string => (err instanceof Error ? err.message : String(err));
 
-async function runOne(dir: string, name: string): Promise<RunResult> {
+async function runOne(
+  dir: string,
+  name: string,
+  { record, prettyErrors, afterEach, noSink }: RunOptions,
+  replay?: Recorded[],
+): Promise<RunResult> {
   // Startup: load and resolve everything before the loop; any failure ends the pipeline.
   const { pipeline, errors } = loadPipeline(dir, name);
   if (pipeline === null) return { name, ok: false, documents: 0, error: errors.join('; ') };
@@ -81,7 +164,16 @@ async function runOne(dir: string, name: string): Promise<RunResult> {
   let sink: { write(text: string): Promise<void> };
   let outFormat: Format;
   try {
-    ({ source, format: inFormat, bounded } = resolveSource(pipeline.source, dir));
+    if (replay !== undefined) {
+      // A replay stands in for the source, which is never opened: the recorded
+      // text in its recorded format, and (like a stream) one bad document doesn't
+      // stop the rest.
+      source = replaySource(replay);
+      inFormat = replay[0]?.format ?? replayFormat(pipeline.source);
+      bounded = false;
+    } else {
+      ({ source, format: inFormat, bounded } = resolveSource(pipeline.source, dir));
+    }
     ({ sink, format: outFormat } = resolveSink(pipeline.sink, dir, inFormat));
   } catch (err) {
     return { name, ok: false, documents: 0, error: message(err) };
@@ -89,15 +181,36 @@ async function runOne(dir: string, name: string): Promise<RunResult> {
 
   // The run loop: one iteration per document the source yields.
   let documents = 0;
+  let skipped = 0;
+  let discarded = 0;
   const docErrors: string[] = [];
   try {
     for await (const text of source.documents()) {
       documents += 1;
+      if (record !== undefined) {
+        const recordedAt = new Date().toISOString();
+        await appendRecord(record, {
+          pipeline: name,
+          document: documents,
+          format: inFormat,
+          recordedAt,
+          payload: text,
+        });
+      }
       try {
         const out = applyFlow(parse[inFormat](text), flow, { functions });
-        await sink.write(serialize[outFormat](out));
+        if (skipsOutput(flow, out)) {
+          skipped += 1;
+          continue;
+        }
+        const rendered = serialize[outFormat](out);
+        if (noSink) discarded += 1;
+        else await sink.write(rendered);
       } catch (err) {
-        const scoped = `document ${documents}: ${message(err)}`;
+        const failure = { flow: pipeline.flow, document: documents, error: err };
+        const scoped = prettyErrors
+          ? renderFailure(failure, prettyErrors.color)
+          : `document ${documents}: ${message(err)}`;
         // Bounded source: the only document failed, so the pipeline fails. Unbounded:
         // log it and keep the stream alive.
         if (bounded) return { name, ok: false, documents, error: scoped };
@@ -109,5 +222,47 @@ async function runOne(dir: string, name: string): Promise<RunResult> {
     return { name, ok: false, documents, error: message(err) };
   }
 
-  return { name, ok: true, documents, docErrors: docErrors.length > 0 ? docErrors : undefined };
+  // A batch is done once a bounded source drains; a stream or replay never is.
+  // Under noSink there is no output for the command to pick up.
+  let hook: HookResult | undefined;
+  if (afterEach !== undefined && bounded && !noSink) {
+    const output = pipeline.sink.type === 'file' ? resolve(dir, pipeline.sink.path as string) : '';
+    hook = runHook(afterEach, dir, { pipeline: name, flow: pipeline.flow, output });
+  }
+  return {
+    name,
+    ok: true,
+    documents,
+    skipped: skipped > 0 ? skipped : undefined,
+    discarded: noSink ? discarded : undefined,
+    docErrors: docErrors.length > 0 ? docErrors : undefined,
+    afterEach: hook,
+  };
+}
+
+/**
+ * Run an `--after-each` command through the shell in the project directory,
+ * naming what just finished in `WEAVSTER_PIPELINE`, `WEAVSTER_FLOW`, and
+ * `WEAVSTER_OUTPUT` (the sink's absolute path; empty for a stdout sink). Its
+ * output goes to stderr so a stdout sink stays pipeable.
+ */
+function runHook(
+  command: string,
+  cwd: string,
+  finished: { pipeline: string; flow: string; output: string },
+): HookResult {
+  const result = spawnSync(command, {
+    shell: true,
+    cwd,
+    stdio: ['ignore', process.stderr, process.stderr],
+    env: {
+      ...process.env,
+      WEAVSTER_PIPELINE: finished.pipeline,
+      WEAVSTER_FLOW: finished.flow,
+      WEAVSTER_OUTPUT: finished.output,
+    },
+  });
+  if (result.error !== undefined) return { exitCode: null, error: result.error.message };
+  if (result.signal !== null) return { exitCode: null, error: `killed by ${result.signal}` };
+  return { exitCode: result.status };
 }
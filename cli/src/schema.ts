@@ -5,12 +5,14 @@ import projectSchema from '../../spec/schemas/project.schema.json' with { type:
 import flowSchema from '../../spec/schemas/flow.schema.json' with { type: 'json' };
 import pipelineSchema from '../../spec/schemas/pipeline.schema.json' with { type: 'json' };
 import manifestSchema from '../../spec/schemas/manifest.schema.json' with { type: 'json' };
+import simSchema from '../../spec/schemas/sim.schema.json' with { type: 'json' };
 
 const ajv = new Ajv({ allErrors: true });
 const validate = ajv.compile(projectSchema);
 const validateFlowSchema = ajv.compile(flowSchema);
 const validatePipelineSchema = ajv.compile(pipelineSchema);
 const validateManifestSchema = ajv.compile(manifestSchema);
+const validateSimSchema = ajv.compile(simSchema);
 
 export interface ValidationResult {
   valid: boolean;
@@ -45,6 +47,13 @@ export function validateManifest(data: unknown): ValidationResult {
   return { valid: false, errors: (validateManifestSchema.errors ?? []).map(formatError) };
 }
 
+/** Validate an already-parsed `flow simulate` spec against the simulation schema. */
+export function validateSimSpec(data: unknown): ValidationResult {
+  const valid = validateSimSchema(data) as boolean;
+  if (valid) return { valid: true, errors: [] };
+  return { valid: false, errors: (validateSimSchema.errors ?? []).map(formatError) };
+}
+
 /** Turn one Ajv error into a path-aware, human-readable line. */
 function formatError(error: ErrorObject): string {
   const path = error.instancePath || '(root)';
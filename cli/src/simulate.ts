@@ -0,0 +1,165 @@
+import { readFileSync } from 'node:fs';
+import { setTimeout as sleep } from 'node:timers/promises';
+import { parse, YAMLParseError } from 'yaml';
+import {
+  type SimSpec,
+  applyFlow,
+  createSimulator,
+  json,
+  skipsOutput,
+  toValue,
+} from '@weavster/core';
+import type { Sink } from './connectors.js';
+import { loadFlow } from './flow.js';
+import { loadFunctions } from './functions.js';
+import { validateSimSpec } from './schema.js';
+
+export interface SimulateOptions {
+  /** Documents per second. */
+  rate: number;
+  durationMs: number;
+  /** Overrides the spec's seed. */
+  seed?: number;
+  /** Where each output goes, one JSON line apiece; without one, outputs are discarded. */
+  sink?: Sink;
+  /** How many outputs to keep as a sample for the report (default 3). */
+  samples?: number;
+}
+
+export interface SimulateReport {
+  ok: boolean;
+  /** Why the simulation could not start. */
+  errors: string[];
+  generated: number;
+  transformed: number;
+  /** Outputs dropped as empty under the flow's `onEmptyOutput: skip`. */
+  skipped: number;
+  failed: number;
+  elapsedMs: number;
+  /** Time to parse, transform, and serialize one document, across the run. */
+  latencyMs: { p50: number; p99: number };
+  /** The first outputs, as written. */
+  samples: string[];
+  /** The first few per-document failures. */
+  failures: string[];
+}
+
+/** Failures kept for the report; the count covers the rest. */
+const MAX_FAILURES = 5;
+
+const message = (err: unknown): string => (err instanceof Error ? err.message : String(err));
+
+/** Load and schema-check a simulation spec file. */
+export function loadSimSpec(file: string): { spec: SimSpec | null; errors: string[] } {
+  let data: unknown;
+  try {
+    data = parse(readFileSync(file, 'utf8'));
+  } catch (err) {
+    const reason = err instanceof YAMLParseError ? `invalid YAML: ${err.message}` : message(err);
+    return { spec: null, errors: [reason] };
+  }
+  const { valid, errors } = validateSimSpec(data);
+  return valid ? { spec: data as SimSpec, errors: [] } : { spec: null, errors };
+}
+
+/** `200/s`, `6000/m`, or a bare `200` (per second) → documents per second. */
+export function parseRate(text: string): number | undefined {
+  const match = /^(\d+(?:\.\d+)?)(?:\/(s|m))?$/.exec(text.trim());
+  if (match === null) return undefined;
+  const rate = Number(match[1]) / (match[2] === 'm' ? 60 : 1);
+  return rate > 0 ? rate : undefined;
+}
+
+/** `500ms`, `30s`, or `5m` → milliseconds. */
+export function parseDuration(text: string): number | undefined {
+  const match = /^(\d+)(ms|s|m)$/.exec(text.trim());
+  if (match === null) return undefined;
+  const ms = Number(match[1]) * { ms: 1, s: 1000, m: 60_000 }[match[2] as 'ms' | 's' | 'm'];
+  return ms > 0 ? ms : undefined;
+}
+
+/** The value at quantile `q` of an ascending array (nearest rank). */
+const quantile = (sorted: number[], q: number): number =>
+  sorted.length === 0 ? 0 : sorted[Math.min(sorted.length - 1, Math.ceil(q * sorted.length) - 1)];
+
+/**
+ * Feed a flow synthetic documents from `spec` at a steady `rate` for
+ * `durationMs`: each is generated, serialized, and run through parse → flow →
+ * serialize exactly as `weavster run` does with a JSON source. Documents are
+ * spaced evenly; a flow slower than the rate falls behind, and the report's
+ * count shows by how much.
+ */
+export async function simulateFlow(
+  projectDir: string,
+  name: string,
+  spec: SimSpec,
+  options: SimulateOptions,
+): Promise<SimulateReport> {
+  const report: SimulateReport = {
+    ok: false,
+    errors: [],
+    generated: 0,
+    transformed: 0,
+    skipped: 0,
+    failed: 0,
+    elapsedMs: 0,
+    latencyMs: { p50: 0, p99: 0 },
+    samples: [],
+    failures: [],
+  };
+  const { flow, errors } = loadFlow(projectDir, name);
+  if (flow === null) return { ...report, errors: errors.map((e) => `flow "${name}": ${e}`) };
+  const { functions, errors: fnErrors } = await loadFunctions(projectDir, flow);
+  if (fnErrors.length > 0) return { ...report, errors: fnErrors };
+
+  let next: () => unknown;
+  try {
+    next = createSimulator(spec, { seed: options.seed });
+  } catch (err) {
+    return { ...report, errors: [message(err)] };
+  }
+
+  const sampleCount = options.samples ?? 3;
+  const interval = 1000 / options.rate;
+  const latencies: number[] = [];
+  const started = performance.now();
+  for (let i = 0; ; i++) {
+    // Document i is due at i intervals in; one that falls behind the clock still
+    // stops at the end of the window.
+    const due = i * interval;
+    if (due >= options.durationMs || performance.now() - started >= options.durationMs) break;
+    const wait = started + due - performance.now();
+    if (wait > 0) await sleep(wait);
+
+    const text = JSON.stringify(next());
+    report.generated += 1;
+    const begun = performance.now();
+    try {
+      const out = applyFlow(json.parse(text), flow, { functions });
+      if (skipsOutput(flow, out)) {
+        report.skipped += 1;
+        latencies.push(performance.now() - begun);
+        continue;
+      }
+      const rendered = JSON.stringify(toValue(out.root));
+      latencies.push(performance.now() - begun);
+      report.transformed += 1;
+      if (report.samples.length < sampleCount) report.samples.push(rendered);
+      await options.sink?.write(`${rendered}\n`);
+    } catch (err) {
+      report.failed += 1;
+      if (report.failures.length < MAX_FAILURES) {
+        report.failures.push(`document ${report.generated}: ${message(err)}`);
+      }
+    }
+  }
+  // The last document is due before the window ends; the run lasts the whole window.
+  const rest = started + options.durationMs - performance.now();
+  if (rest > 0) await sleep(rest);
+  report.elapsedMs = performance.now() - started;
+
+  latencies.sort((a, b) => a - b);
+  report.latencyMs = { p50: quantile(latencies, 0.5), p99: quantile(latencies, 0.99) };
+  report.ok = report.failed === 0;
+  return report;
+}
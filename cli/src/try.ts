@@ -0,0 +1,49 @@
+import { parse, YAMLParseError } from 'yaml';
+import { applyFlow, document, fromValue, toValue } from '@weavster/core';
+import { checkFlow } from './flow.js';
+
+export interface TryResult {
+  /** The transformed record, or `null` when the steps or input were rejected. */
+  output: unknown;
+  errors: string[];
+}
+
+/**
+ * Accept what an author would paste: a whole flow (`steps: [...]`), a list of
+ * steps, or a single step.
+ */
+function asFlow(data: unknown): unknown {
+  if (Array.isArray(data)) return { steps: data };
+  if (data !== null && typeof data === 'object' && !('steps' in data)) return { steps: [data] };
+  return data;
+}
+
+/**
+ * Apply a YAML snippet of steps to one JSON record, checked exactly as a flow
+ * file is. For trying the DSL out without a project; `_ts` steps have no
+ * functions to call here.
+ */
+export function trySteps(stepsYaml: string, inputJson: string): TryResult {
+  let data: unknown;
+  try {
+    data = parse(stepsYaml);
+  } catch (err) {
+    const message = err instanceof YAMLParseError ? err.message : String(err);
+    return { output: null, errors: [`invalid YAML: ${message}`] };
+  }
+  const { flow, errors } = checkFlow(asFlow(data));
+  if (!flow) return { output: null, errors };
+
+  let input: unknown;
+  try {
+    input = JSON.parse(inputJson);
+  } catch (err) {
+    return { output: null, errors: [`invalid JSON input: ${(err as Error).message}`] };
+  }
+  try {
+    const out = applyFlow(document(fromValue(input), { sourceFormat: 'json' }), flow);
+    return { output: toValue(out.root), errors: [] };
+  } catch (err) {
+    return { output: null, errors: [err instanceof Error ? err.message : String(err)] };
+  }
+}
@@ -0,0 +1,179 @@
+import { createHash } from 'node:crypto';
+import { existsSync, mkdirSync, readFileSync, watch, writeFileSync } from 'node:fs';
+import { basename, dirname, join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+import { OPERATORS } from '@weavster/core';
+import cliPackage from '../package.json' with { type: 'json' };
+import projectSchema from '../../spec/schemas/project.schema.json' with { type: 'json' };
+import flowSchema from '../../spec/schemas/flow.schema.json' with { type: 'json' };
+import pipelineSchema from '../../spec/schemas/pipeline.schema.json' with { type: 'json' };
+import { listFlows, loadFlow } from './flow.js';
+import { listPipelines, loadPipeline } from './pipeline.js';
+import { checkProject, resolveProjectFile } from './project.js';
+
+/** One checked file: the project config, a flow, or a pipeline. */
+export interface FileReport {
+  file: string;
+  ok: boolean;
+  errors: string[];
+}
+
+export interface ValidateReport {
+  ok: boolean;
+  projectDir: string;
+  files: FileReport[];
+  /** Files actually re-checked this run; the rest reused their cached diagnostics. */
+  checked: string[];
+}
+
+export interface ValidateOptions {
+  /** Ignore the cached state and re-check every file. */
+  full?: boolean;
+}
+
+/** Per-file content hashes and the diagnostics they produced, kept between runs. */
+interface ValidateState {
+  validator: string;
+  files: Record<string, { sha256: string; errors: string[] }>;
+}
+
+const STATE_FILE = join('.weavster', 'validate-state.json');
+
+/**
+ * The code running this module. The published CLI is one bundle with
+ * `@weavster/core` inlined, so this covers every rule that decides a file's
+ * diagnostics: path grammar, module, containment, and direction checks alike.
+ * Unreadable, it is a fresh value, so nothing cached is ever reused.
+ */
+function codeDigest(): string {
+  try {
+    return createHash('sha256')
+      .update(readFileSync(fileURLToPath(import.meta.url)))
+      .digest('hex');
+  } catch {
+    return `unread-${process.pid}-${Date.now()}`;
+  }
+}
+
+/**
+ * Cached diagnostics are only as good as the rules that produced them, so the
+ * state is keyed to the CLI version, the code, the schemas, and the operator
+ * catalog: an upgrade that changes any of them starts from a clean run.
+ */
+const VALIDATOR = createHash('sha256')
+  .update(
+    JSON.stringify([
+      cliPackage.version,
+      codeDigest(),
+      projectSchema,
+      flowSchema,
+      pipelineSchema,
+      OPERATORS,
+    ]),
+  )
+  .digest('hex');
+
+function readState(projectDir: string): ValidateState['files'] {
+  try {
+    const state = JSON.parse(readFileSync(join(projectDir, STATE_FILE), 'utf8')) as ValidateState;
+    return state.validator === VALIDATOR ? state.files : {};
+  } catch {
+    return {};
+  }
+}
+
+/** Best effort: a project we cannot write to still validates, just never incrementally. */
+function writeState(projectDir: string, files: ValidateState['files']): void {
+  try {
+    mkdirSync(join(projectDir, '.weavster'), { recursive: true });
+    const state: ValidateState = { validator: VALIDATOR, files };
+    writeFileSync(join(projectDir, STATE_FILE), `${JSON.stringify(state)}\n`);
+  } catch {
+    // The next run re-checks everything, which is correct, only slower.
+  }
+}
+
+function hashFile(file: string): string | undefined {
+  try {
+    return createHash('sha256').update(readFileSync(file)).digest('hex');
+  } catch {
+    return undefined;
+  }
+}
+
+/** Changes under these never affect validation: build output, dependencies, dot-dirs. */
+const IGNORED = /^(target|node_modules|\.[^/\\]+)([/\\]|$)/;
+
+/**
+ * Validate a project's weavster.yaml, then every flow and pipeline in it. A
+ * file whose content is unchanged since the last run reuses the diagnostics
+ * recorded in `.weavster/validate-state.json`, so the report is the same as a
+ * full run's. Each file is checked on its own (connectors are inline in
+ * pipelines, and flows reference nothing but `functions/` names), so a changed
+ * file has no dependents to re-check.
+ */
+export function validateAll(path: string, options: ValidateOptions = {}): ValidateReport {
+  const projectFile = resolveProjectFile(path);
+  const found = existsSync(projectFile);
+  const projectDir = found ? dirname(projectFile) : path;
+  const shown = found ? projectFile : path;
+  const previous = options.full ? {} : readState(projectDir);
+  const next: ValidateState['files'] = {};
+  const checked: string[] = [];
+
+  const check = (key: string, file: string, display: string, run: () => string[]) => {
+    const sha256 = hashFile(file);
+    const cached = previous[key];
+    let errors: string[];
+    if (sha256 !== undefined && cached?.sha256 === sha256) {
+      errors = cached.errors;
+    } else {
+      errors = run();
+      checked.push(display);
+    }
+    if (sha256 !== undefined) next[key] = { sha256, errors };
+    return { file: display, ok: errors.length === 0, errors };
+  };
+
+  const files: FileReport[] = [
+    check(basename(projectFile), projectFile, shown, () => checkProject(path).errors),
+    ...listFlows(projectDir).map((name) => {
+      const key = `flows/${name}.yaml`;
+      return check(key, join(projectDir, key), key, () => loadFlow(projectDir, name).errors);
+    }),
+    ...listPipelines(projectDir).map((name) => {
+      const key = `pipelines/${name}.yaml`;
+      return check(key, join(projectDir, key), key, () => loadPipeline(projectDir, name).errors);
+    }),
+  ];
+  if (found) writeState(projectDir, next);
+  return { ok: files.every((f) => f.ok), projectDir, files, checked };
+}
+
+/**
+ * Validate now, then again whenever a file in the project changes, handing each
+ * report to `onReport`. A burst of changes (an editor's save, a branch switch)
+ * settles for `debounceMs` and yields one report. `options` applies to the first
+ * run; later runs re-check only what changed. Returns a function that stops
+ * watching.
+ */
+export function watchProject(
+  path: string,
+  onReport: (report: ValidateReport) => void,
+  debounceMs = 100,
+  options: ValidateOptions = {},
+): () => void {
+  const first = validateAll(path, options);
+  onReport(first);
+
+  let timer: NodeJS.Timeout | undefined;
+  const watcher = watch(first.projectDir, { recursive: true }, (_event, filename) => {
+    if (filename !== null && IGNORED.test(filename)) return;
+    clearTimeout(timer);
+    timer = setTimeout(() => onReport(validateAll(path)), debounceMs);
+  });
+  return () => {
+    clearTimeout(timer);
+    watcher.close();
+  };
+}
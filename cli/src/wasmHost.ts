@@ -6,6 +6,8 @@ import { WASI } from 'node:wasi';
 /** The result envelope a flow module writes to stdout (docs/ARTIFACT_SPEC.md). */
 export interface ResultEnvelope {
   ok: boolean;
+  /** The output was dropped as empty under the flow's `onEmptyOutput: skip`; no payload. */
+  skipped?: boolean;
   payload?: string;
   error?: { stage: string; type?: string; message?: string };
 }
@@ -13,8 +15,8 @@ export interface ResultEnvelope {
 /**
  * The Node WASI host: drive a compiled Javy flow module once over the
  * stdin/stdout envelope ABI — the exact contract the Rust engine host uses
- * (`engine/src/host.rs`). This is the second host the E6 parity test diffs
- * against the engine; both drive the *same* wasm, so a byte difference means a
+ * (`engine/src/host.rs`). `compile --test` runs fixtures through it, and it is
+ * the second host the E6 parity test diffs against the engine; both drive the *same* wasm, so a byte difference means a
  * host disagreed, not two JS engines. A string `input` is written verbatim (to
  * exercise malformed-envelope handling); anything else is JSON-encoded.
  */
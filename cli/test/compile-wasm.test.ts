@@ -1,10 +1,12 @@
+import { createHash } from 'node:crypto';
 import { existsSync, mkdirSync, mkdtempSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
 import { tmpdir } from 'node:os';
 import { dirname, join, resolve } from 'node:path';
 import { fileURLToPath } from 'node:url';
 import { afterAll, beforeAll, describe, expect, it } from 'vitest';
-import { compile } from '../src/compile.js';
-import { runEnvelope } from './wasmHost.js';
+import { type CompileResult, compile, formatJson } from '../src/compile.js';
+import { checkCompiledFixtures, compiledCaseError } from '../src/fixtures.js';
+import { runEnvelope } from '../src/wasmHost.js';
 
 // End-to-end: compile the golden-path project to a real wasm artifact via Javy,
 // then drive each flow module over the stdin/stdout envelope through Node's WASI
@@ -15,13 +17,14 @@ const goldenPath = resolve(here, '../../examples/golden-path');
 
 let outDir: string;
 let wasm: Buffer;
+let result: CompileResult;
 
 beforeAll(async () => {
   outDir = mkdtempSync(join(tmpdir(), 'wv-artifact-'));
   // Pre-seed a stale module from a "previous run" so the wipe is observable.
   mkdirSync(join(outDir, 'flows'), { recursive: true });
   writeFileSync(join(outDir, 'flows', 'stale.wasm'), 'old');
-  const result = await compile(goldenPath, outDir);
+  result = await compile(goldenPath, outDir);
   expect(result.errors).toEqual([]);
   expect(result.ok).toBe(true);
   wasm = readFileSync(join(outDir, 'flows', 'order.wasm'));
@@ -29,7 +32,7 @@ beforeAll(async () => {
 
 afterAll(() => rmSync(outDir, { recursive: true, force: true }));
 
-// Drive the compiled module over the Node WASI host (test/wasmHost.ts).
+// Drive the compiled module over the Node WASI host (src/wasmHost.ts).
 const run = (input: unknown) => runEnvelope(wasm, input);
 
 describe('compiled artifact', () => {
@@ -42,6 +45,28 @@ describe('compiled artifact', () => {
     expect(existsSync(join(outDir, 'flows', 'stale.wasm'))).toBe(false);
   });
 
+  it('reports the compiled flow with its size and shape', () => {
+    const sha256 = createHash('sha256').update(wasm).digest('hex');
+    expect(result.flows).toEqual([
+      {
+        flow: 'order',
+        path: 'flows/order.wasm',
+        sha256,
+        bytes: wasm.length,
+        steps: 5,
+        functions: 1,
+      },
+    ]);
+    expect(result.flows[0].bytes).toBeGreaterThan(0);
+  });
+
+  it('prints the same report as JSON for CI', () => {
+    const json = JSON.parse(formatJson(result));
+    expect(json).toMatchObject({ ok: true, manifest: join(outDir, 'manifest.json'), errors: [] });
+    expect(json.pipelines).toEqual(['order']);
+    expect(json.flows).toEqual(result.flows);
+  });
+
   it('runs the flow through the wasm envelope', () => {
     const payload = JSON.stringify({ id: 'a1', first: 'Ada', last: 'Lovelace', status: 'new' });
     const result = run({ in: 'json', out: 'json', payload });
@@ -73,3 +98,43 @@ describe('compiled artifact', () => {
     expect(result.error?.stage).toBe('envelope');
   });
 });
+
+describe('compile --test fixture check', () => {
+  it('passes when the module agrees with every fixture', () => {
+    expect(checkCompiledFixtures(goldenPath, 'order', wasm)).toEqual([]);
+  });
+
+  it('names the case whose expected output the module disagrees with', () => {
+    const project = mkdtempSync(join(tmpdir(), 'wv-fixtures-'));
+    const caseDir = join(project, 'fixtures', 'order', 'drifted');
+    mkdirSync(caseDir, { recursive: true });
+    // The expected output forgot that the flow uppercases the id.
+    const input = { id: 'a-1', status: 'new', first: 'jane', last: 'doe' };
+    const expected = { ...input, name: 'jane doe', priority: 'high', initials: 'JD' };
+    writeFileSync(join(caseDir, 'input.json'), JSON.stringify(input));
+    writeFileSync(join(caseDir, 'expected.json'), JSON.stringify(expected));
+    try {
+      const [error, ...rest] = checkCompiledFixtures(project, 'order', wasm);
+      expect(rest).toEqual([]);
+      expect(error).toMatch(/^order\/drifted: compiled module disagrees with expected\.json\n/);
+      expect(error).toContain('+   "id": "A-1"');
+    } finally {
+      rmSync(project, { recursive: true, force: true });
+    }
+  });
+
+  it('compares an output the module skipped as empty against {}', () => {
+    // What the bundled handle() returns when onEmptyOutput: skip drops the output.
+    const skipped = { ok: true, skipped: true };
+    expect(compiledCaseError('drop/empty', skipped, '{}')).toBeUndefined();
+    const error = compiledCaseError('drop/empty', skipped, '{ "id": 1 }');
+    expect(error).toMatch(
+      /^drop\/empty: compiled module skipped the output as empty, unlike expected\.json\n/,
+    );
+    expect(error).toContain('-   "id": 1');
+  });
+
+  it('has nothing to check for a flow without fixtures', () => {
+    expect(checkCompiledFixtures(goldenPath, 'no-such-flow', wasm)).toEqual([]);
+  });
+});
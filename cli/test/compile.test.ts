@@ -2,7 +2,7 @@ import { existsSync, mkdirSync, mkdtempSync, rmSync, writeFileSync } from 'node:
 import { tmpdir } from 'node:os';
 import { join } from 'node:path';
 import { afterEach, beforeEach, describe, expect, it } from 'vitest';
-import { buildManifest, compile } from '../src/compile.js';
+import { buildManifest, compile, formatJson, formatReport } from '../src/compile.js';
 import { javyCompile } from '../src/javy.js';
 import { validateManifest } from '../src/schema.js';
 
@@ -85,7 +85,7 @@ describe('buildManifest', () => {
     expect(errors.join('\n')).toMatch(/invalid YAML/);
   });
 
-  it('rejects a non-file connector', () => {
+  it('rejects a connector the engine cannot run', () => {
     writeFileSync(
       join(dir, 'pipelines', 'order.yaml'),
       'source: { type: stdin, format: json }\nflow: order\nsink: { type: file, path: out/order.json }\n',
@@ -93,7 +93,47 @@ describe('buildManifest', () => {
     writeProject('  - name: order\n');
     const { manifest, errors } = buildManifest(dir);
     expect(manifest).toBeNull();
-    expect(errors.join('\n')).toMatch(/only file connectors/);
+    expect(errors.join('\n')).toMatch(/only file and sqlite connectors/);
+  });
+
+  it('maps sqlite connectors into the manifest as JSON rows', () => {
+    writeFileSync(
+      join(dir, 'pipelines', 'order.yaml'),
+      "source: { type: sqlite, path: db/shop.db, table: orders, where: 'total > 10' }\nflow: order\n" +
+        'sink: { type: sqlite, path: db/out.db, table: orders }\n',
+    );
+    writeProject('  - name: order\n');
+    const { manifest, errors } = buildManifest(dir);
+    expect(errors).toEqual([]);
+    expect(manifest?.pipelines[0].source).toEqual({
+      type: 'sqlite',
+      path: 'db/shop.db',
+      table: 'orders',
+      where: 'total > 10',
+      format: 'json',
+    });
+    expect(manifest?.pipelines[0].sink).toEqual({
+      type: 'sqlite',
+      path: 'db/out.db',
+      table: 'orders',
+      format: 'json',
+    });
+    expect(validateManifest(manifest).valid).toBe(true);
+  });
+
+  it('carries a pipeline rateLimit into the manifest only when set', () => {
+    writeFileSync(
+      join(dir, 'pipelines', 'order.yaml'),
+      'source: { type: file, path: in/o.json }\nflow: order\n' +
+        'sink: { type: file, path: out/o.json }\nrateLimit: 25\n',
+    );
+    writeProject('  - name: order\n');
+    const { manifest, errors } = buildManifest(dir);
+    expect(errors).toEqual([]);
+    expect(manifest?.pipelines[0].rateLimit).toBe(25);
+    expect(validateManifest(manifest).valid).toBe(true);
+    const zero = { ...manifest, pipelines: [{ ...manifest?.pipelines[0], rateLimit: 0 }] };
+    expect(validateManifest(zero).valid).toBe(false);
   });
 
   it('errors when the switchboard names a pipeline with no yaml file', () => {
@@ -113,6 +153,17 @@ describe('buildManifest', () => {
     expect(manifest?.pipelines[0].sink.format).toBe('json');
   });
 
+  it('writes Windows-style connector paths with forward slashes', () => {
+    writeFileSync(
+      join(dir, 'pipelines', 'order.yaml'),
+      "source: { type: file, path: 'in\\orders\\a.json' }\nflow: order\nsink: { type: file, path: 'out\\a.json' }\n",
+    );
+    writeProject('  - name: order\n');
+    const { manifest } = buildManifest(dir);
+    expect(manifest?.pipelines[0].source.glob).toBe('in/orders/a.json');
+    expect(manifest?.pipelines[0].sink.path).toBe('out/a.json');
+  });
+
   it('errors when every switchboard pipeline is disabled', () => {
     writeProject('  - name: order\n    enabled: false\n');
     const { manifest, errors } = buildManifest(dir);
@@ -160,13 +211,45 @@ describe('compile error paths', () => {
     writeProject('  - name: order\n');
 
     const out = join(dir, 'target', 'artifact');
-    const result = await compile(dir, out);
+    const events: string[] = [];
+    const result = await compile(dir, out, {
+      progress: (total) => ({
+        step: (flow) => events.push(`${flow}/${total}`),
+        done: () => events.push('done'),
+      }),
+    });
     expect(result.ok).toBe(false);
     expect(result.errors.join('\n')).toMatch(/not sandbox-safe/);
+    // The failing flow was still reported, and the display cleared after it.
+    expect(events).toEqual(['order/1', 'done']);
     expect(existsSync(join(out, 'manifest.json'))).toBe(false);
   });
 });
 
+describe('formatJson', () => {
+  it('reports a failed compile with its errors and no flows', async () => {
+    const result = await compile(dir, join(dir, 'out'));
+    const json = JSON.parse(formatJson(result));
+    expect(json).toMatchObject({ ok: false, manifest: null, pipelines: [], flows: [] });
+    expect(json.errors.length).toBeGreaterThan(0);
+  });
+});
+
+describe('formatReport', () => {
+  it('renders one aligned row per flow under a header', () => {
+    const module = (flow: string) => ({ flow, path: `flows/${flow}.wasm`, sha256: '' });
+    const table = formatReport([
+      { ...module('order'), bytes: 2_621_440, steps: 5, functions: 1 },
+      { ...module('ping'), bytes: 512, steps: 1, functions: 0 },
+    ]);
+    expect(table.split('\n')).toEqual([
+      'flow         size  steps  functions',
+      'order  2560.0 KiB      5          1',
+      'ping      0.5 KiB      1          0',
+    ]);
+  });
+});
+
 describe('javyCompile', () => {
   it('reports a failed compile with javy stderr detail', () => {
     const result = javyCompile(join(dir, 'missing.js'), join(dir, 'out.wasm'));
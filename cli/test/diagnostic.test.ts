@@ -0,0 +1,41 @@
+import { describe, expect, it } from 'vitest';
+import { applyFlow, document, fromValue } from '@weavster/core';
+import { renderFailure } from '../src/diagnostic.js';
+
+/** The error a flow throws on a document, for rendering. */
+function failureOf(steps: Record<string, unknown>[], value: unknown): unknown {
+  try {
+    applyFlow(document(fromValue(value), { sourceFormat: 'json' }), { steps });
+  } catch (err) {
+    return err;
+  }
+  throw new Error('expected the flow to fail');
+}
+
+describe('renderFailure', () => {
+  const steps = [
+    { _set: { id: { _upper: '$id' } } },
+    { _when: { cond: true, then: [{ _set: { placedAt: { _toIso: '$placed' } } }] } },
+  ];
+  const error = failureOf(steps, { id: 'a-1', placed: 'next tuesday' });
+
+  it('names the flow, the document, the step chain, and the failing field', () => {
+    const text = renderFailure({ flow: 'order', document: 3, error }, false);
+    const lines = text.split('\n');
+    expect(lines[0]).toBe('error: "_toIso" got an unparseable date');
+    expect(lines[1]).toBe('  --> flow order, document 3');
+    expect(lines[2]).toBe('   = at step 1 (_when) › step 0 (_set)');
+    expect(text).toContain('placedAt');
+    expect(text).not.toContain('\x1b[');
+  });
+
+  it('colors the diagnostic only when asked', () => {
+    const text = renderFailure({ flow: 'order', document: 3, error }, true);
+    expect(text.startsWith('\x1b[1;31merror\x1b[0m: ')).toBe(true);
+  });
+
+  it('keeps to the reason and location for a failure outside any step', () => {
+    const text = renderFailure({ flow: 'order', document: 1, error: new Error('bad JSON') }, false);
+    expect(text).toBe('error: bad JSON\n  --> flow order, document 1');
+  });
+});
@@ -1,7 +1,9 @@
 import { describe, expect, it } from 'vitest';
 import { fileURLToPath } from 'node:url';
-import { dirname, resolve } from 'node:path';
-import { checkFlows, listFlows, loadFlow } from '../src/flow.js';
+import { cpSync, mkdirSync, mkdtempSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import { dirname, join, resolve } from 'node:path';
+import { checkFlows, copyFlow, listFlows, loadFlow } from '../src/flow.js';
 
 const here = dirname(fileURLToPath(import.meta.url));
 const goldenPath = resolve(here, '../../examples/golden-path');
@@ -14,6 +16,22 @@ describe('loadFlow', () => {
     expect(Object.keys(flow?.steps[0] ?? {})).toEqual(['_set']);
   });
 
+  it('reports a key repeated inside a step rather than dropping the first', () => {
+    const dir = mkdtempSync(join(tmpdir(), 'wv-dup-'));
+    mkdirSync(join(dir, 'flows'));
+    writeFileSync(
+      join(dir, 'flows', 'order.yaml'),
+      'steps:\n  - _set:\n      id: { _upper: $id }\n      id: $legacyId\n',
+    );
+    try {
+      const { flow, errors } = loadFlow(dir, 'order');
+      expect(flow).toBeNull();
+      expect(errors.join('\n')).toMatch(/invalid YAML: Map keys must be unique at line 4/);
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
   it('reports a missing flow', () => {
     const { flow, errors } = loadFlow(goldenPath, 'nope');
     expect(flow).toBeNull();
@@ -36,3 +54,40 @@ describe('checkFlows', () => {
     expect(checkFlows(goldenPath)).toEqual([{ file: 'flows/order.yaml', ok: true, errors: [] }]);
   });
 });
+
+describe('copyFlow', () => {
+  const withProject = (body: (dir: string) => void) => {
+    const dir = mkdtempSync(join(tmpdir(), 'wv-copy-'));
+    cpSync(goldenPath, dir, { recursive: true });
+    try {
+      body(dir);
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  };
+
+  it('writes a copy that loads under the new name', () =>
+    withProject((dir) => {
+      expect(copyFlow(dir, 'order', 'order-v2')).toEqual({ ok: true, file: 'flows/order-v2.yaml' });
+      expect(listFlows(dir)).toEqual(['order', 'order-v2']);
+      const { flow, errors } = loadFlow(dir, 'order-v2');
+      expect(errors).toEqual([]);
+      expect(flow?.steps).toHaveLength(3);
+      // Verbatim, so the original's comments come along.
+      expect(readFileSync(join(dir, 'flows', 'order-v2.yaml'), 'utf8')).toBe(
+        readFileSync(join(dir, 'flows', 'order.yaml'), 'utf8'),
+      );
+    }));
+
+  it('refuses to overwrite an existing flow', () =>
+    withProject((dir) => {
+      copyFlow(dir, 'order', 'order-v2');
+      expect(copyFlow(dir, 'order', 'order-v2').error).toMatch(/"order-v2" already exists/);
+    }));
+
+  it('refuses a missing source and a name that is not kebab-case', () =>
+    withProject((dir) => {
+      expect(copyFlow(dir, 'nope', 'x').error).toMatch(/no flow "nope"/);
+      expect(copyFlow(dir, 'order', 'Order_2').error).toMatch(/must be kebab-case/);
+    }));
+});
@@ -0,0 +1,31 @@
+import { describe, expect, it } from 'vitest';
+import { checkModules, collectModules } from '../src/functions.js';
+
+describe('collectModules', () => {
+  it('finds _ts modules at the top level and inside _when and _each steps', () => {
+    const steps = [
+      { _ts: { module: 'a' } },
+      { _when: { cond: true, then: [{ _ts: { module: 'b' } }], else: [{ _ts: { module: 'a' } }] } },
+      { _each: { in: 'items', steps: [{ _ts: { module: 'c' } }] } },
+    ];
+    expect(collectModules(steps)).toEqual(['a', 'b', 'c']);
+  });
+});
+
+describe('checkModules', () => {
+  it('accepts names and subfolders under functions/', () => {
+    const steps = [{ _ts: { module: 'initials' } }, { _ts: { module: 'orders/score-v2' } }];
+    expect(checkModules({ steps })).toEqual([]);
+  });
+
+  it('refuses a name that climbs out of functions/ or out of an import string', () => {
+    const steps = [
+      { _ts: { module: '../../secrets' } },
+      { _when: { cond: true, then: [{ _ts: { module: "x'; import 'evil" } }] } },
+    ];
+    expect(checkModules({ steps })).toEqual([
+      '_ts module "../../secrets" must be a name under functions/ (letters, digits, _, -, /)',
+      '_ts module "x\'; import \'evil" must be a name under functions/ (letters, digits, _, -, /)',
+    ]);
+  });
+});
@@ -5,10 +5,10 @@ import { dirname, join, resolve } from 'node:path';
 import { fileURLToPath } from 'node:url';
 import { afterAll, beforeAll, describe, expect, it } from 'vitest';
 import { compile } from '../src/compile.js';
-import { runEnvelope } from './wasmHost.js';
+import { runEnvelope } from '../src/wasmHost.js';
 
 // E6 parity guardrail (RFC 0003 slice 6). Drive the SAME compiled order.wasm
-// through both harnesses — the Node WASI host (test/wasmHost.ts) and the Rust
+// through both harnesses — the Node WASI host (src/wasmHost.ts) and the Rust
 // engine binary — and assert byte-equal output. Because both drive one wasm,
 // a difference means the two *hosts* disagree on I/O, envelope encoding, or
 // serialization; it is not a comparison of two JS engines.
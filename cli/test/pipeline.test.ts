@@ -0,0 +1,109 @@
+import { mkdirSync, mkdtempSync, rmSync, writeFileSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+import { afterEach, beforeEach, describe, expect, it } from 'vitest';
+import {
+  describeConnector,
+  loadPipeline,
+  pipelinesUsing,
+  resolveSink,
+  resolveSource,
+} from '../src/pipeline.js';
+
+let dir: string;
+beforeEach(() => {
+  dir = mkdtempSync(join(tmpdir(), 'wv-pipeline-'));
+  mkdirSync(join(dir, 'pipelines'));
+});
+afterEach(() => rmSync(dir, { recursive: true, force: true }));
+
+const load = (source: string, sink: string) => {
+  writeFileSync(
+    join(dir, 'pipelines', 'p.yaml'),
+    `source: { type: file, path: "${source}" }\nflow: f\nsink: { type: file, path: "${sink}" }\n`,
+  );
+  return loadPipeline(dir, 'p');
+};
+
+describe('loadPipeline connector paths', () => {
+  it('accepts paths inside the project', () => {
+    expect(load('in/order.json', 'out/nested/order.json').errors).toEqual([]);
+  });
+
+  it('refuses absolute paths and ".." traversal, naming the field', () => {
+    expect(load('/etc/passwd', 'out/x.json').errors).toEqual([
+      'source.path "/etc/passwd" must be relative to the project',
+    ]);
+    expect(load('in/x.json', '../../outside.json').errors).toEqual([
+      'sink.path "../../outside.json" must not contain ".."',
+    ]);
+  });
+});
+
+describe('loadPipeline connector directions', () => {
+  const loadRaw = (yaml: string) => {
+    writeFileSync(join(dir, 'pipelines', 'p.yaml'), yaml);
+    return loadPipeline(dir, 'p');
+  };
+
+  it('names a sink-only connector used as the source', () => {
+    const yaml = 'source: { type: stdout }\nflow: f\nsink: { type: stdout }\n';
+    expect(loadRaw(yaml).errors).toEqual([
+      'source.type "stdout" is a sink; a source is file, sqlite, or stdin',
+    ]);
+  });
+
+  it('names a source-only connector used as the sink', () => {
+    const yaml = 'source: { type: stdin, format: json }\nflow: f\nsink: { type: stdin }\n';
+    expect(loadRaw(yaml).errors).toEqual([
+      'sink.type "stdin" is a source; a sink is file, sqlite, or stdout',
+    ]);
+  });
+});
+
+describe('sqlite connectors', () => {
+  it('validate as a source and a sink', () => {
+    writeFileSync(
+      join(dir, 'pipelines', 'p.yaml'),
+      'source: { type: sqlite, path: shop.db, table: orders }\nflow: f\n' +
+        'sink: { type: sqlite, path: shop.db, table: shipped }\n',
+    );
+    expect(loadPipeline(dir, 'p').errors).toEqual([]);
+  });
+
+  it('are refused by run, which has no SQLite driver', () => {
+    const spec = { type: 'sqlite', path: 'shop.db', table: 'orders' };
+    expect(() => resolveSource(spec, dir)).toThrow(/runs in the engine only/);
+    expect(() => resolveSink(spec, dir, 'json')).toThrow(/runs in the engine only/);
+  });
+});
+
+describe('pipelinesUsing', () => {
+  it('lists the pipelines that route through a flow, in name order', () => {
+    const write = (name: string, flow: string) =>
+      writeFileSync(
+        join(dir, 'pipelines', `${name}.yaml`),
+        `source: { type: file, path: in/${name}.json }\nflow: ${flow}\n` +
+          `sink: { type: file, path: out/${name}.json }\n`,
+      );
+    write('refunds', 'orders');
+    write('audit', 'orders');
+    write('ticks', 'clock');
+    writeFileSync(join(dir, 'pipelines', 'broken.yaml'), 'flow: [orders\n');
+
+    const using = pipelinesUsing(dir, 'orders');
+    expect(using.map(([name]) => name)).toEqual(['audit', 'refunds']);
+    expect(using[0][1].source).toEqual({ type: 'file', path: 'in/audit.json' });
+    expect(pipelinesUsing(dir, 'missing')).toEqual([]);
+  });
+});
+
+describe('describeConnector', () => {
+  it('names the type and the settings a connector carries', () => {
+    expect(describeConnector({ type: 'file', path: 'in/order.xml', format: 'xml' })).toBe(
+      'file in/order.xml as xml',
+    );
+    expect(describeConnector({ type: 'stdin', format: 'json' })).toBe('stdin as json');
+    expect(describeConnector({ type: 'stdout' }, (text) => `\`${text}\``)).toBe('`stdout`');
+  });
+});
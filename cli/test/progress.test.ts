@@ -0,0 +1,30 @@
+import { describe, expect, it } from 'vitest';
+import { progress } from '../src/progress.js';
+
+const capture = (isTTY: boolean) => {
+  const chunks: string[] = [];
+  return { chunks, stream: { isTTY, write: (chunk: string) => chunks.push(chunk) } };
+};
+
+describe('progress', () => {
+  it('redraws one status line per step on a TTY and clears it when done', () => {
+    const { chunks, stream } = capture(true);
+    const status = progress(2, 'compiling', stream);
+    status.step('order');
+    status.step('refund');
+    status.done();
+    expect(chunks).toEqual([
+      '\r\x1b[2K[1/2] compiling order',
+      '\r\x1b[2K[2/2] compiling refund',
+      '\r\x1b[2K',
+    ]);
+  });
+
+  it('writes nothing, and so no control characters, when not a TTY', () => {
+    const { chunks, stream } = capture(false);
+    const status = progress(2, 'compiling', stream);
+    status.step('order');
+    status.done();
+    expect(chunks).toEqual([]);
+  });
+});
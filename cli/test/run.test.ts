@@ -1,11 +1,12 @@
 import { afterEach, beforeEach, describe, expect, it } from 'vitest';
 import { fileURLToPath } from 'node:url';
-import { mkdirSync, mkdtempSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
+import { existsSync, mkdirSync, mkdtempSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
 import { tmpdir } from 'node:os';
 import { dirname, join, resolve } from 'node:path';
 import { Readable } from 'node:stream';
 import { runPipelines } from '../src/runner.js';
 import { checkPipelines, resolveSink, resolveSource } from '../src/pipeline.js';
+import { readRecording } from '../src/recording.js';
 
 const here = dirname(fileURLToPath(import.meta.url));
 const goldenPath = resolve(here, '../../examples/golden-path');
@@ -96,6 +97,156 @@ describe('runPipelines', () => {
     expect(report.ok).toBe(false);
     expect(report.results[0].error).toMatch(/no input file/);
   });
+
+  it('applies the flow onEmptyOutput policy to an emptied document', async () => {
+    writePipeline(
+      'p',
+      'source: { type: file, path: in/x.json }\nflow: wipe\nsink: { type: file, path: out/x.json }\n',
+    );
+    const wipe = (policy: string) =>
+      writeFileSync(
+        join(dir, 'flows', 'wipe.yaml'),
+        `steps:\n  - _unset: [id]\n${policy ? `onEmptyOutput: ${policy}\n` : ''}`,
+      );
+
+    wipe('');
+    const skipped = await runPipelines(dir, 'p');
+    expect(skipped.results[0]).toMatchObject({ ok: true, documents: 1, skipped: 1 });
+    expect(existsSync(join(dir, 'out', 'x.json'))).toBe(false);
+
+    wipe('error');
+    const failed = await runPipelines(dir, 'p');
+    expect(failed.results[0].error).toMatch(/document 1: .*empty document/);
+
+    wipe('allow');
+    expect((await runPipelines(dir, 'p')).ok).toBe(true);
+    expect(readFileSync(join(dir, 'out', 'x.json'), 'utf8').trim()).toBe('{}');
+  });
+});
+
+describe('record and replay', () => {
+  it('replays recorded documents through the flow to the same output', async () => {
+    writePipeline(
+      'p',
+      'source: { type: file, path: in/x.json }\nflow: main\nsink: { type: file, path: out/x.json }\n',
+    );
+    const recording = join(dir, 'rec', 'p.jsonl');
+    expect((await runPipelines(dir, 'p', { record: recording })).ok).toBe(true);
+    const live = readFileSync(join(dir, 'out', 'x.json'), 'utf8');
+
+    const [line] = readFileSync(recording, 'utf8').trim().split('\n');
+    expect(JSON.parse(line)).toMatchObject({
+      pipeline: 'p',
+      document: 1,
+      format: 'json',
+      payload: '{ "id": 1 }',
+    });
+
+    // The source is gone; the replay alone reproduces the output.
+    rmSync(join(dir, 'in'), { recursive: true });
+    rmSync(join(dir, 'out'), { recursive: true });
+    const replayed = await runPipelines(dir, undefined, { replay: recording });
+    expect(replayed.results).toEqual([{ name: 'p', ok: true, documents: 1 }]);
+    expect(readFileSync(join(dir, 'out', 'x.json'), 'utf8')).toBe(live);
+  });
+
+  it('replays a pipeline whose source cannot be opened', async () => {
+    // `weavster run` cannot open a sqlite source, and no format fits a .dat file;
+    // neither matters when the recording stands in for the source.
+    writePipeline(
+      'db',
+      'source: { type: sqlite, path: gone.db, table: orders }\nflow: main\n' +
+        'sink: { type: file, path: out/db.json }\n',
+    );
+    writePipeline(
+      'dat',
+      'source: { type: file, path: in/x.dat }\nflow: main\nsink: { type: file, path: out/dat.json }\n',
+    );
+    const recording = join(dir, 'rec.jsonl');
+    const lines = ['db', 'dat'].map((pipeline) =>
+      JSON.stringify({ pipeline, document: 1, format: 'json', payload: '{ "id": 7 }' }),
+    );
+    writeFileSync(recording, `${lines.join('\n')}\n`);
+
+    const report = await runPipelines(dir, undefined, { replay: recording });
+    expect(report.errors).toEqual([]);
+    expect(report.results.map((r) => [r.name, r.ok, r.documents])).toEqual([
+      ['db', true, 1],
+      ['dat', true, 1],
+    ]);
+    expect(JSON.parse(readFileSync(join(dir, 'out', 'db.json'), 'utf8'))).toMatchObject({ id: 7 });
+  });
+
+  it('reads a recording saved with CRLF line endings', async () => {
+    const recording = join(dir, 'crlf.jsonl');
+    const line = JSON.stringify({ pipeline: 'p', payload: '{}' });
+    writeFileSync(recording, `${line}\r\n${line}\r\n`);
+    expect(await readRecording(recording)).toHaveLength(2);
+  });
+
+  it('names the line of a malformed recording', async () => {
+    const recording = join(dir, 'bad.jsonl');
+    writeFileSync(recording, '{"pipeline":"p","payload":"{}"}\nnot json\n');
+    const report = await runPipelines(dir, undefined, { replay: recording });
+    expect(report.ok).toBe(false);
+    expect(report.errors.join('\n')).toMatch(/bad\.jsonl:2: invalid recording line/);
+  });
+});
+
+describe('--no-sink', () => {
+  it('runs every document through the flow but writes nothing', async () => {
+    writePipeline(
+      'p',
+      'source: { type: file, path: in/x.json }\nflow: main\nsink: { type: file, path: out/x.json }\n',
+    );
+    const report = await runPipelines(dir, 'p', { noSink: true, afterEach: 'touch hook.txt' });
+    expect(report.ok).toBe(true);
+    expect(report.results).toEqual([{ name: 'p', ok: true, documents: 1, discarded: 1 }]);
+    expect(existsSync(join(dir, 'out'))).toBe(false);
+    // Nothing was written, so there is nothing for --after-each to act on.
+    expect(existsSync(join(dir, 'hook.txt'))).toBe(false);
+  });
+
+  it('still fails a document the flow cannot transform', async () => {
+    writeFileSync(join(dir, 'in', 'bad.json'), '{ not json');
+    writePipeline(
+      'p',
+      'source: { type: file, path: in/bad.json }\nflow: main\nsink: { type: file, path: out/x.json }\n',
+    );
+    const report = await runPipelines(dir, 'p', { noSink: true });
+    expect(report.ok).toBe(false);
+    expect(report.results[0].documents).toBe(1);
+  });
+});
+
+describe('--after-each', () => {
+  it('runs once the file source drains, naming the pipeline and its output', async () => {
+    writePipeline(
+      'p',
+      'source: { type: file, path: in/x.json }\nflow: main\nsink: { type: file, path: out/x.json }\n',
+    );
+    // Runs in the project directory; reading the output proves the sink was written first.
+    const command = [
+      'echo "$WEAVSTER_PIPELINE $WEAVSTER_FLOW" > hook.txt',
+      'cat "$WEAVSTER_OUTPUT" >> hook.txt',
+    ].join('; ');
+    const report = await runPipelines(dir, 'p', { afterEach: command });
+    expect(report.ok).toBe(true);
+    expect(report.results[0].afterEach).toEqual({ exitCode: 0 });
+    const [names, ...output] = readFileSync(join(dir, 'hook.txt'), 'utf8').split('\n');
+    expect(names).toBe('p main');
+    expect(JSON.parse(output.join('\n'))).toEqual({ id: 1, status: 'new' });
+  });
+
+  it('fails the run when the command exits non-zero', async () => {
+    writePipeline(
+      'p',
+      'source: { type: file, path: in/x.json }\nflow: main\nsink: { type: file, path: out/x.json }\n',
+    );
+    const report = await runPipelines(dir, 'p', { afterEach: 'exit 3' });
+    expect(report.ok).toBe(false);
+    expect(report.results[0]).toMatchObject({ ok: true, afterEach: { exitCode: 3 } });
+  });
 });
 
 describe('format resolution', () => {
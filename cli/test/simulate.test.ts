@@ -0,0 +1,94 @@
+import { afterEach, beforeEach, describe, expect, it } from 'vitest';
+import { mkdirSync, mkdtempSync, rmSync, writeFileSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+import type { SimSpec } from '@weavster/core';
+import { loadSimSpec, parseDuration, parseRate, simulateFlow } from '../src/simulate.js';
+
+let dir: string;
+beforeEach(() => {
+  dir = mkdtempSync(join(tmpdir(), 'wv-simulate-'));
+  mkdirSync(join(dir, 'flows'));
+  writeFileSync(
+    join(dir, 'flows', 'order.yaml'),
+    'steps:\n  - _set:\n' +
+      '      priority: { _cond: { if: { _gt: [$total, 100] }, then: high, else: low } }\n',
+  );
+});
+afterEach(() => rmSync(dir, { recursive: true, force: true }));
+
+const spec: SimSpec = {
+  seed: 1,
+  fields: {
+    id: { type: 'uuid' },
+    customer: { type: 'object', fields: { name: { type: 'name' } } },
+    total: { type: 'int', min: 1, max: 200 },
+  },
+};
+
+describe('simulateFlow', () => {
+  it('runs a second of generated documents through the flow at the given rate', async () => {
+    const written: string[] = [];
+    const sink = { write: async (text: string) => void written.push(text) };
+    const report = await simulateFlow(dir, 'order', spec, { rate: 20, durationMs: 1000, sink });
+
+    expect(report.errors).toEqual([]);
+    expect(report.ok).toBe(true);
+    expect(report.generated).toBe(20);
+    expect(report.transformed).toBe(20);
+    expect(report.failed).toBe(0);
+    expect(report.elapsedMs).toBeGreaterThanOrEqual(990);
+    expect(report.elapsedMs).toBeLessThan(2000);
+    expect(written).toHaveLength(20);
+    const first = JSON.parse(written[0]);
+    expect(first.priority).toBe(first.total > 100 ? 'high' : 'low');
+    expect(report.samples).toEqual(written.slice(0, 3).map((line) => line.trimEnd()));
+  }, 5_000);
+
+  it('discards outputs without a sink, and repeats its documents for a seed', async () => {
+    const run = () => simulateFlow(dir, 'order', spec, { rate: 100, durationMs: 100 });
+    const [a, b] = [await run(), await run()];
+    expect(a.transformed).toBeGreaterThan(0);
+    expect(a.samples).toEqual(b.samples);
+  });
+
+  it('reports a flow that does not exist instead of running', async () => {
+    const report = await simulateFlow(dir, 'ghost', spec, { rate: 10, durationMs: 100 });
+    expect(report.ok).toBe(false);
+    expect(report.generated).toBe(0);
+    expect(report.errors.join('\n')).toMatch(/flow "ghost": no flow "ghost"/);
+  });
+});
+
+describe('loadSimSpec', () => {
+  it('parses a spec file', () => {
+    const file = join(dir, 'sim.yaml');
+    writeFileSync(file, 'seed: 3\nfields:\n  status: { type: enum, values: [new, shipped] }\n');
+    expect(loadSimSpec(file)).toEqual({
+      spec: { seed: 3, fields: { status: { type: 'enum', values: ['new', 'shipped'] } } },
+      errors: [],
+    });
+  });
+
+  it('rejects a spec whose generator has an unknown type', () => {
+    const file = join(dir, 'sim.yaml');
+    writeFileSync(file, 'fields:\n  id: { type: guid }\n');
+    const { spec, errors } = loadSimSpec(file);
+    expect(spec).toBeNull();
+    expect(errors.join('\n')).toContain('/fields/id');
+  });
+});
+
+describe('parseRate and parseDuration', () => {
+  it('reads the command-line forms', () => {
+    expect(parseRate('200/s')).toBe(200);
+    expect(parseRate('6000/m')).toBe(100);
+    expect(parseRate('50')).toBe(50);
+    expect(parseRate('0/s')).toBeUndefined();
+    expect(parseRate('fast')).toBeUndefined();
+    expect(parseDuration('500ms')).toBe(500);
+    expect(parseDuration('60s')).toBe(60_000);
+    expect(parseDuration('5m')).toBe(300_000);
+    expect(parseDuration('60')).toBeUndefined();
+  });
+});
@@ -46,9 +46,24 @@ describe('runFixtures', () => {
     ]);
   });
 
+  it('runs inline flow examples, naming each case and diffing a failure', async () => {
+    const run = await runFixtures(resolve(harness, 'examples'));
+    expect(run.ok).toBe(false);
+    expect(run.errors).toEqual([]);
+    expect(run.results.map((r) => [r.name, r.ok])).toEqual([
+      ['tag/tags-and-drops', true],
+      ['tag/example-2', false],
+      ['wipe/empties', true],
+    ]);
+    // The diff runs expected (-) against what the flow's steps produced (+).
+    expect(run.results[1].diff).toContain('-   "a": 2');
+    expect(run.results[1].diff).toContain('+   "a": 1');
+  });
+
   it('reports a missing fixtures directory', async () => {
     const run = await runFixtures(resolve(harness, 'does-not-exist'));
     expect(run.ok).toBe(false);
     expect(run.errors.join('\n')).toContain('no fixtures/ directory');
+    expect(run.errors.join('\n')).toContain('no flow has examples');
   });
 });
@@ -0,0 +1,30 @@
+import { describe, expect, it } from 'vitest';
+import { trySteps } from '../src/try.js';
+
+describe('trySteps', () => {
+  it('applies a single step to a record', () => {
+    const { output, errors } = trySteps(
+      '_set: { id: { _upper: $id } }',
+      '{ "id": "a-1", "qty": 2 }',
+    );
+    expect(errors).toEqual([]);
+    expect(output).toEqual({ id: 'A-1', qty: 2 });
+  });
+
+  it('accepts a list of steps or a whole flow', () => {
+    const list = '- _rename: { sku: code }\n- _unset: [internal]\n';
+    const record = '{ "sku": "W", "internal": true }';
+    expect(trySteps(list, record).output).toEqual({ code: 'W' });
+    expect(trySteps(`steps:\n${list}`, record).output).toEqual({ code: 'W' });
+  });
+
+  it('checks the steps like a flow file and reports runtime errors', () => {
+    expect(trySteps('_set: { a: { _lookup: $b } }', '{}').errors).toEqual([
+      'step 0 (_set): unknown operator "_lookup"',
+    ]);
+    expect(trySteps('_append: { to: a, value: 1 }', '{ "a": 1 }').errors).toEqual([
+      'step 0 (_append): "_append" target "a" is not an array',
+    ]);
+    expect(trySteps('_set: { a: 1 }', '{ nope').errors[0]).toMatch(/^invalid JSON input: /);
+  });
+});
@@ -1,7 +1,10 @@
 import { describe, expect, it } from 'vitest';
+import { mkdirSync, mkdtempSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
+import { tmpdir } from 'node:os';
 import { fileURLToPath } from 'node:url';
-import { dirname, resolve } from 'node:path';
+import { dirname, join, resolve } from 'node:path';
 import { checkProject } from '../src/project.js';
+import { type ValidateReport, validateAll, watchProject } from '../src/validate.js';
 
 const here = dirname(fileURLToPath(import.meta.url));
 const examples = resolve(here, '../../spec/examples/project');
@@ -32,6 +35,12 @@ describe('checkProject', () => {
     expect(result.errors.join('\n')).toContain('unknown property "flavor"');
   });
 
+  it('reports a repeated key with its line instead of keeping the last value', () => {
+    const result = checkProject(example('invalid-duplicate-key.weavster.yaml'));
+    expect(result.ok).toBe(false);
+    expect(result.errors.join('\n')).toMatch(/Map keys must be unique at line 4/);
+  });
+
   it('reports a name that breaks the pattern', () => {
     const result = checkProject(example('invalid-bad-name.weavster.yaml'));
     expect(result.ok).toBe(false);
@@ -45,3 +54,90 @@ describe('checkProject', () => {
     expect(result.errors.join('\n')).toContain('no weavster.yaml');
   });
 });
+
+describe('validateAll', () => {
+  it('re-checks only the files that changed since the last run', () => {
+    const dir = mkdtempSync(join(tmpdir(), 'wv-incremental-'));
+    writeFileSync(join(dir, 'weavster.yaml'), 'apiVersion: weavster/v0alpha2\nname: t\n');
+    mkdirSync(join(dir, 'flows'));
+    for (const name of ['a', 'b', 'c']) {
+      writeFileSync(join(dir, 'flows', `${name}.yaml`), 'steps:\n  - _set: { status: new }\n');
+    }
+    mkdirSync(join(dir, 'pipelines'));
+    writeFileSync(
+      join(dir, 'pipelines', 'p.yaml'),
+      'source: { type: file, path: in.json }\nflow: a\nsink: { type: file, path: out.json }\n',
+    );
+    try {
+      const first = validateAll(dir);
+      expect(first.checked).toHaveLength(5);
+
+      expect(validateAll(dir).checked).toEqual([]);
+
+      writeFileSync(join(dir, 'flows', 'b.yaml'), 'steps: [\n');
+      const incremental = validateAll(dir);
+      expect(incremental.checked).toEqual(['flows/b.yaml']);
+      expect(incremental.ok).toBe(false);
+
+      // Cached diagnostics replay exactly: the report matches a clean run's.
+      const cached = validateAll(dir);
+      expect(cached.checked).toEqual([]);
+      const full = validateAll(dir, { full: true });
+      expect(full.checked).toHaveLength(5);
+      expect(cached.files).toEqual(full.files);
+      expect(incremental.files).toEqual(full.files);
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  it('re-checks every file when the state was written by another validator', () => {
+    const dir = mkdtempSync(join(tmpdir(), 'wv-validator-'));
+    writeFileSync(join(dir, 'weavster.yaml'), 'apiVersion: weavster/v0alpha2\nname: t\n');
+    mkdirSync(join(dir, 'flows'));
+    writeFileSync(join(dir, 'flows', 'a.yaml'), 'steps:\n  - _set: { status: new }\n');
+    try {
+      expect(validateAll(dir).checked).toHaveLength(2);
+      expect(validateAll(dir).checked).toEqual([]);
+
+      // As after a CLI upgrade: same files, same hashes, another validator key.
+      const stateFile = join(dir, '.weavster', 'validate-state.json');
+      const state = JSON.parse(readFileSync(stateFile, 'utf8'));
+      writeFileSync(stateFile, JSON.stringify({ ...state, validator: 'older-cli' }));
+      expect(validateAll(dir).checked).toEqual([join(dir, 'weavster.yaml'), 'flows/a.yaml']);
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+});
+
+describe('watchProject', () => {
+  it('reports a flow broken after the watch starts on the next cycle', async () => {
+    const dir = mkdtempSync(join(tmpdir(), 'wv-watch-'));
+    writeFileSync(join(dir, 'weavster.yaml'), 'apiVersion: weavster/v0alpha2\nname: t\n');
+    mkdirSync(join(dir, 'flows'));
+    writeFileSync(join(dir, 'flows', 'main.yaml'), 'steps:\n  - _set: { status: new }\n');
+
+    const reports: ValidateReport[] = [];
+    let next: (report: ValidateReport) => void = () => {};
+    const stop = watchProject(
+      dir,
+      (report) => {
+        reports.push(report);
+        next(report);
+      },
+      20,
+    );
+    try {
+      expect(reports.map((r) => r.ok)).toEqual([true]);
+      const changed = new Promise<ValidateReport>((resolve) => (next = resolve));
+      writeFileSync(join(dir, 'flows', 'main.yaml'), 'steps: [\n');
+      const report = await changed;
+      expect(report.ok).toBe(false);
+      expect(report.files.find((f) => f.file === 'flows/main.yaml')?.ok).toBe(false);
+    } finally {
+      stop();
+      rmSync(dir, { recursive: true, force: true });
+    }
+  }, 5_000);
+});
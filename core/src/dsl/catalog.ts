@@ -0,0 +1,163 @@
+/**
+ * The operator catalog (`weavster schema operators`): every step and value
+ * operator the engine implements, with the shape of its argument and what it
+ * does. A test holds the catalog to the engine's own tables, so an operator
+ * can't ship without an entry.
+ */
+import type { ExplainFormat } from './explain.js';
+
+export type OperatorKind = 'step' | 'value';
+
+export interface OperatorInfo {
+  name: string;
+  kind: OperatorKind;
+  /** The argument, in the notation of the DSL reference (`{ <path>: <expr>, ... }`). */
+  shape: string;
+  does: string;
+}
+
+export type CatalogFormat = ExplainFormat | 'json';
+
+export const OPERATORS: OperatorInfo[] = [
+  { name: '_set', kind: 'step', shape: '{ <path>: <expr>, ... }', does: 'set each path' },
+  {
+    name: '_default',
+    kind: 'step',
+    shape: '{ <path>: <expr>, ... }',
+    does: 'set each path only where it is absent',
+  },
+  { name: '_unset', kind: 'step', shape: '[<path>, ...]', does: 'remove paths' },
+  { name: '_rename', kind: 'step', shape: '{ <from>: <to>, ... }', does: 'move paths' },
+  {
+    name: '_append',
+    kind: 'step',
+    shape: '{ to: <path>, value: <expr> }',
+    does: 'append to an array (created if absent)',
+  },
+  {
+    name: '_merge',
+    kind: 'step',
+    shape: '{ value: <expr>, strategy?: keep|overwrite }',
+    does: 'deep-merge an object into the document',
+  },
+  {
+    name: '_nullify',
+    kind: 'step',
+    shape: '{ paths?: [<path>, ...], tokens?: [...] }',
+    does: 'turn null-like strings into real nulls',
+  },
+  {
+    name: '_select',
+    kind: 'step',
+    shape: '{ <path>: <expr>, ... }',
+    does: 'output only the named paths',
+  },
+  { name: '_keep', kind: 'step', shape: '[<path>, ...]', does: 'keep only these paths' },
+  {
+    name: '_parseJson',
+    kind: 'step',
+    shape: '[<path>, ...]',
+    does: 'parse JSON strings into nested data',
+  },
+  {
+    name: '_stringifyJson',
+    kind: 'step',
+    shape: '[<path>, ...]',
+    does: 'replace values with their JSON text',
+  },
+  {
+    name: '_when',
+    kind: 'step',
+    shape: '{ cond: <expr>, then: [steps], else?: [steps] }',
+    does: 'run a branch by condition',
+  },
+  {
+    name: '_each',
+    kind: 'step',
+    shape: '{ in: <path>, steps: [steps] }',
+    does: 'run steps on each object element of an array',
+  },
+  { name: '_ts', kind: 'step', shape: '{ module, from?, to? }', does: 'run a custom function' },
+  {
+    name: '_concat',
+    kind: 'value',
+    shape: '[<expr>, ...] or { parts, sep }',
+    does: 'joined string',
+  },
+  { name: '_upper', kind: 'value', shape: '<expr>', does: 'uppercased string' },
+  { name: '_lower', kind: 'value', shape: '<expr>', does: 'lowercased string' },
+  { name: '_trim', kind: 'value', shape: '<expr>', does: 'string without surrounding spaces' },
+  { name: '_toIso', kind: 'value', shape: '<expr>', does: 'date string as ISO-8601 UTC' },
+  { name: '_coalesce', kind: 'value', shape: '[<expr>, ...]', does: 'first non-null value' },
+  { name: '_eq', kind: 'value', shape: '[<expr>, <expr>]', does: 'true if equal' },
+  { name: '_gt', kind: 'value', shape: '[<expr>, <expr>]', does: 'true if greater' },
+  { name: '_lt', kind: 'value', shape: '[<expr>, <expr>]', does: 'true if less' },
+  { name: '_in', kind: 'value', shape: '[<needle>, <arrayExpr>]', does: 'membership boolean' },
+  { name: '_exists', kind: 'value', shape: '<expr>', does: 'true if the value is present' },
+  { name: '_and', kind: 'value', shape: '[<expr>, ...]', does: 'true if every value is' },
+  { name: '_or', kind: 'value', shape: '[<expr>, ...]', does: 'true if any value is' },
+  { name: '_not', kind: 'value', shape: '<expr>', does: 'boolean negation' },
+  {
+    name: '_cond',
+    kind: 'value',
+    shape: '{ if: <expr>, then: <expr>, else: <expr> }',
+    does: 'a value chosen by a condition',
+  },
+  { name: '_uuid5', kind: 'value', shape: '[<namespace>, <expr>]', does: 'a deterministic UUID' },
+];
+
+/** Edit distance between two operator names, for "did you mean" hints. */
+function distance(a: string, b: string): number {
+  let row = Array.from({ length: b.length + 1 }, (_, j) => j);
+  for (let i = 1; i <= a.length; i++) {
+    const next = [i];
+    for (let j = 1; j <= b.length; j++) {
+      const cost = a[i - 1] === b[j - 1] ? 0 : 1;
+      next.push(Math.min(row[j] + 1, next[j - 1] + 1, row[j - 1] + cost));
+    }
+    row = next;
+  }
+  return row[b.length];
+}
+
+/** The catalogued operator of `kind` closest to a misspelled `name`, if any is close. */
+export function suggestOperator(name: string, kind: OperatorKind): string | undefined {
+  let best: string | undefined;
+  let bestDistance = 3;
+  for (const op of OPERATORS) {
+    if (op.kind !== kind) continue;
+    const d = distance(name.toLowerCase(), op.name.toLowerCase());
+    if (d < bestDistance) [best, bestDistance] = [op.name, d];
+  }
+  return best;
+}
+
+/**
+ * Render the catalog: aligned columns (`text`), a table per kind for the docs
+ * (`markdown`), or the entries themselves (`json`).
+ */
+export function describeOperators(format: CatalogFormat): string {
+  if (format === 'json') return JSON.stringify(OPERATORS, null, 2);
+  const sections: [OperatorKind, string][] = [
+    ['step', 'Steps'],
+    ['value', 'Value operators'],
+  ];
+  return sections
+    .map(([kind, title]) => {
+      const ops = OPERATORS.filter((op) => op.kind === kind);
+      if (format === 'markdown') {
+        // A `|` inside a shape would end its cell.
+        const shape = (op: OperatorInfo) => op.shape.replaceAll('|', '\\|');
+        const rows = ops.map((op) => `| \`${op.name}\` | \`${shape(op)}\` | ${op.does} |`);
+        const header = ['| Operator | Shape | Does |', '| --- | --- | --- |'];
+        return [`### ${title}`, '', ...header, ...rows].join('\n');
+      }
+      const nameWidth = Math.max(...ops.map((op) => op.name.length));
+      const shapeWidth = Math.max(...ops.map((op) => op.shape.length));
+      const rows = ops.map(
+        (op) => `  ${op.name.padEnd(nameWidth)}  ${op.shape.padEnd(shapeWidth)}  ${op.does}`,
+      );
+      return [title, ...rows].join('\n');
+    })
+    .join('\n\n');
+}
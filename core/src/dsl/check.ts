@@ -0,0 +1,253 @@
+/**
+ * Static checks over a flow, run at load time (`weavster validate`, and before
+ * `test`/`run`/`compile` use a flow) so a mistake surfaces with its step
+ * rather than on the first document that reaches it. `lintFlow` adds
+ * best-practice warnings for steps that are valid but can't be what was meant.
+ */
+import { document } from '../model.js';
+import { formatPath, parsePath } from '../path.js';
+import { suggestOperator } from './catalog.js';
+import { type Flow, type Step, keepIndexMessage } from './engine.js';
+import { VALUE_OPS, evalExpr } from './expr.js';
+
+/** Path arguments of each step, by operator: which keys/values are paths. */
+function stepPaths(op: string, arg: unknown): string[] {
+  const record =
+    arg !== null && typeof arg === 'object' && !Array.isArray(arg)
+      ? (arg as Record<string, unknown>)
+      : {};
+  const strings = (...values: unknown[]) => values.filter((v) => typeof v === 'string');
+  switch (op) {
+    case '_set':
+    case '_default':
+    case '_select':
+      return Object.keys(record);
+    case '_unset':
+    case '_keep':
+    case '_parseJson':
+    case '_stringifyJson':
+      return Array.isArray(arg) ? strings(...arg) : [];
+    case '_rename':
+      return [...Object.keys(record), ...strings(...Object.values(record))];
+    case '_append':
+      return strings(record.to);
+    case '_each':
+      return strings(record.in);
+    case '_nullify':
+      return Array.isArray(record.paths) ? strings(...record.paths) : [];
+    case '_ts':
+      return strings(record.from, record.to);
+    default:
+      return [];
+  }
+}
+
+/** Every `$path` reference inside an expression (skipping `_lit` and `$$` escapes). */
+function refs(expr: unknown): string[] {
+  if (typeof expr === 'string') {
+    return expr.startsWith('$') && !expr.startsWith('$$') ? [expr.slice(1)] : [];
+  }
+  if (Array.isArray(expr)) return expr.flatMap(refs);
+  if (expr !== null && typeof expr === 'object') {
+    return Object.entries(expr as Record<string, unknown>).flatMap(([key, value]) =>
+      key === '_lit' ? [] : refs(value),
+    );
+  }
+  return [];
+}
+
+/** Expression positions of each step (where `$path` references live). */
+function stepExprs(op: string, arg: unknown): unknown[] {
+  if (arg === null || typeof arg !== 'object' || Array.isArray(arg)) return [];
+  const record = arg as Record<string, unknown>;
+  switch (op) {
+    case '_set':
+    case '_default':
+    case '_select':
+      return Object.values(record);
+    case '_append':
+    case '_merge':
+      return [record.value];
+    case '_when':
+      return [record.cond];
+    default:
+      return [];
+  }
+}
+
+/** Nested step lists (`_when` branches, `_each` bodies). */
+function childSteps(op: string, arg: unknown): Step[][] {
+  if (arg === null || typeof arg !== 'object') return [];
+  const record = arg as Record<string, unknown>;
+  const keys: Record<string, string[]> = { _when: ['then', 'else'], _each: ['steps'] };
+  return (keys[op] ?? []).filter((k) => Array.isArray(record[k])).map((k) => record[k] as Step[]);
+}
+
+function checkSteps(steps: Step[]): string[] {
+  return steps.flatMap((step, index) => {
+    const [op] = Object.keys(step);
+    const arg = step[op];
+    const context = `step ${index} (${op})`;
+    const errors: string[] = [];
+    for (const path of [...stepPaths(op, arg), ...stepExprs(op, arg).flatMap(refs)]) {
+      try {
+        const segments = parsePath(path);
+        if (op === '_keep' && segments.some((segment) => typeof segment === 'number')) {
+          errors.push(`${context}: ${keepIndexMessage(path)}`);
+        }
+      } catch (err) {
+        errors.push(`${context}: ${err instanceof Error ? err.message : String(err)}`);
+      }
+    }
+    for (const child of childSteps(op, arg)) {
+      errors.push(...checkSteps(child).map((e) => `${context}: ${e}`));
+    }
+    return errors;
+  });
+}
+
+/**
+ * Check every path a flow names — step targets and `$path` references — against
+ * the path grammar, and that no `_keep` path indexes into an array. Returns one
+ * message per bad path, scoped like a runtime `TransformError`
+ * (`step 1 (_set): invalid path ...`).
+ */
+export function checkPaths(flow: Flow): string[] {
+  return checkSteps(flow.steps);
+}
+
+/** Every `_op` inside an expression that no value operator implements. */
+function unknownOperators(expr: unknown): string[] {
+  if (Array.isArray(expr)) return expr.flatMap(unknownOperators);
+  if (expr === null || typeof expr !== 'object') return [];
+  const entries = Object.entries(expr as Record<string, unknown>);
+  if (entries.length === 1 && entries[0][0].startsWith('_')) {
+    const [op, arg] = entries[0];
+    if (op === '_lit') return [];
+    return [...(op in VALUE_OPS ? [] : [op]), ...unknownOperators(arg)];
+  }
+  return entries.flatMap(([, value]) => unknownOperators(value));
+}
+
+function checkOperatorSteps(steps: Step[]): string[] {
+  return steps.flatMap((step, index) => {
+    const [op] = Object.keys(step);
+    const arg = step[op];
+    const context = `step ${index} (${op})`;
+    const errors = stepExprs(op, arg)
+      .flatMap(unknownOperators)
+      .map((name) => {
+        const hint = suggestOperator(name, 'value');
+        return `${context}: unknown operator "${name}"${hint ? ` (did you mean "${hint}"?)` : ''}`;
+      });
+    for (const child of childSteps(op, arg)) {
+      errors.push(...checkOperatorSteps(child).map((e) => `${context}: ${e}`));
+    }
+    return errors;
+  });
+}
+
+/**
+ * Check that every value operator a flow uses exists. The schema already pins
+ * step operators; an unknown `_op` inside a value would otherwise only fail
+ * when a document reaches it — or never, in a branch no fixture takes.
+ */
+export function checkOperators(flow: Flow): string[] {
+  return checkOperatorSteps(flow.steps);
+}
+
+/** A path in canonical string form, so `a["b"]` and `a.b` compare equal. */
+function canonical(path: string): string | undefined {
+  try {
+    return formatPath(parsePath(path));
+  } catch {
+    return undefined;
+  }
+}
+
+/** The paths a step writes a value to (not counting removals). */
+function writtenPaths(step: Step): string[] {
+  const [op] = Object.keys(step);
+  const arg = step[op];
+  if (op !== '_set' || arg === null || typeof arg !== 'object') return [];
+  return Object.keys(arg as Record<string, unknown>);
+}
+
+/**
+ * The truthiness of a condition that reads no `$path`, by evaluating it once
+ * against an empty document; `undefined` when it depends on the document (or
+ * can't be evaluated statically).
+ */
+function constantCondition(cond: unknown): boolean | undefined {
+  if (refs(cond).length > 0) return undefined;
+  try {
+    const working = document({ kind: 'object', fields: {} });
+    return Boolean(evalExpr(cond, { working, functions: {} }));
+  } catch {
+    return undefined;
+  }
+}
+
+function lintSteps(steps: Step[]): string[] {
+  return steps.flatMap((step, index) => {
+    const [op] = Object.keys(step);
+    const arg = step[op];
+    const context = `step ${index} (${op})`;
+    const record =
+      arg !== null && typeof arg === 'object' && !Array.isArray(arg)
+        ? (arg as Record<string, unknown>)
+        : {};
+    const warnings: string[] = [];
+
+    if (op === '_set') {
+      for (const [path, expr] of Object.entries(record)) {
+        const source = typeof expr === 'string' && !expr.startsWith('$$') && expr.startsWith('$');
+        if (source && canonical(expr.slice(1)) === canonical(path)) {
+          warnings.push(`${context}: sets "${path}" to itself (${expr}); this does nothing`);
+        }
+      }
+    }
+    if (op === '_rename') {
+      for (const [from, to] of Object.entries(record)) {
+        if (typeof to === 'string' && canonical(from) === canonical(to)) {
+          warnings.push(`${context}: renames "${from}" to itself; this does nothing`);
+        }
+      }
+    }
+    if (op === '_unset' && Array.isArray(arg) && index + 1 < steps.length) {
+      const next = steps[index + 1];
+      const rewritten = new Set(writtenPaths(next).map(canonical));
+      for (const path of arg) {
+        if (typeof path === 'string' && rewritten.has(canonical(path))) {
+          const nextOp = Object.keys(next)[0];
+          warnings.push(
+            `${context}: removes "${path}", which step ${index + 1} (${nextOp}) sets again`,
+          );
+        }
+      }
+    }
+    if (op === '_when') {
+      const always = constantCondition(record.cond);
+      if (always === true) {
+        warnings.push(`${context}: condition is always true, so "then" always runs`);
+      } else if (always === false) {
+        warnings.push(`${context}: condition is always false, so "then" never runs`);
+      }
+    }
+
+    for (const child of childSteps(op, arg)) {
+      warnings.push(...lintSteps(child).map((w) => `${context}: ${w}`));
+    }
+    return warnings;
+  });
+}
+
+/**
+ * Best-practice warnings for a valid flow: a `_set` of a path to itself, a
+ * `_rename` onto the same path, an `_unset` the next `_set` undoes, and a
+ * `_when` whose condition reads nothing from the document (so one branch is
+ * dead). Scoped like `checkPaths` messages; an empty list means nothing to flag.
+ */
+export function lintFlow(flow: Flow): string[] {
+  return lintSteps(flow.steps);
+}
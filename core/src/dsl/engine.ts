@@ -6,19 +6,41 @@
  * change only the paths they name and leave the rest of the document intact.
  * Values are expressions (see `expr.ts`).
  */
-import { type Document, type Node, fromValue, toValue } from '../model.js';
-import { get, remove, set } from '../path.js';
+import { type Document, type Node, type ObjectNode, fromValue, toValue } from '../model.js';
+import { get, parsePath, remove, set } from '../path.js';
 import { type Ctx, type TransformFn, evalExpr } from './expr.js';
 import { TransformError } from './errors.js';
 
-export { TransformError } from './errors.js';
+export { type StepFrame, TransformError } from './errors.js';
 export type { TransformFn } from './expr.js';
 
 /** A single transform step: one `_`-prefixed operator key mapped to its argument. */
 export type Step = Record<string, unknown>;
 
+/** What to do when a flow's output is an empty object (see `onEmptyOutput`). */
+export type EmptyOutputPolicy = 'allow' | 'skip' | 'error';
+
 export interface Flow {
   steps: Step[];
+  /**
+   * Guard against a flow that strips every field: `skip` (the default) drops
+   * an empty output instead of writing it, `error` fails the document, `allow`
+   * writes it.
+   */
+  onEmptyOutput?: EmptyOutputPolicy;
+  /** Inline test cases, run by `weavster test`; running the flow ignores them. */
+  examples?: FlowExample[];
+}
+
+/**
+ * An inline test case: run `input` through the flow and compare the output to
+ * `expect`, or, with `expectSkipped`, check the output is dropped as empty.
+ */
+export interface FlowExample {
+  name?: string;
+  input: unknown;
+  expect?: unknown;
+  expectSkipped?: true;
 }
 
 export interface RunOptions {
@@ -35,6 +57,44 @@ function toJson(value: unknown): unknown {
   }
 }
 
+/**
+ * Deep-merge `patch` into `target` in place. Objects merge field by field; any
+ * other pairing (scalars, arrays, mismatched kinds) is a conflict, which the
+ * patch wins only when `overwrite` is set. Arrays are values, not merged.
+ */
+function mergeNode(target: ObjectNode, patch: ObjectNode, overwrite: boolean): void {
+  for (const [key, incoming] of Object.entries(patch.fields)) {
+    const existing = target.fields[key];
+    if (existing === undefined) {
+      target.fields[key] = incoming;
+    } else if (existing.kind === 'object' && incoming.kind === 'object') {
+      mergeNode(existing, incoming, overwrite);
+    } else if (overwrite) {
+      target.fields[key] = incoming;
+    }
+  }
+}
+
+/** Strings `_nullify` treats as null when no `tokens` are given. */
+export const NULL_TOKENS = ['null', 'NULL', 'N/A', ''];
+
+/** Replace every string leaf under `node` that is one of `tokens` with null, in place. */
+function nullifyNode(node: Node, tokens: Set<string>): Node {
+  if (node.kind === 'scalar') {
+    return typeof node.value === 'string' && tokens.has(node.value)
+      ? { kind: 'scalar', value: null }
+      : node;
+  }
+  if (node.kind === 'object') {
+    for (const [key, child] of Object.entries(node.fields)) {
+      node.fields[key] = nullifyNode(child, tokens);
+    }
+  } else {
+    node.items = node.items.map((item) => nullifyNode(item, tokens));
+  }
+  return node;
+}
+
 type StructuralOp = (working: Document, arg: unknown, ctx: Ctx) => void;
 
 function asRecord(arg: unknown, op: string): Record<string, unknown> {
@@ -44,6 +104,25 @@ function asRecord(arg: unknown, op: string): Record<string, unknown> {
   return arg as Record<string, unknown>;
 }
 
+/**
+ * Why `JSON.parse` refused its input, without the input itself: Node 20 quotes
+ * the start of the text in its messages, and a document error is logged.
+ */
+function jsonSyntaxReason(err: unknown): string {
+  const message = err instanceof Error ? err.message : '';
+  if (message.includes('end of JSON input')) return 'unexpected end of input';
+  const position = /at position (\d+)/.exec(message);
+  if (position === null) return 'syntax error';
+  return `syntax error at character ${Number(position[1]) + 1}`;
+}
+
+/**
+ * `_keep` copies fields into a fresh object, so it keeps whole arrays, never
+ * single elements; a path with an index is refused, here and by `checkPaths`.
+ */
+export const keepIndexMessage = (path: string): string =>
+  `"_keep" path "${path}" has an array index; _keep keeps whole arrays, so list the array itself`;
+
 const STRUCTURAL: Record<string, StructuralOp> = {
   /** Patch: set each path to its evaluated expression. Missing (undefined) values are skipped. */
   _set(working, arg, ctx) {
@@ -100,6 +179,48 @@ const STRUCTURAL: Record<string, StructuralOp> = {
     }
   },
 
+  /**
+   * Deep-merge an object into the document. Document values win on conflict
+   * (`strategy: keep`, the default); `strategy: overwrite` lets the merged value win.
+   */
+  _merge(working, arg, ctx) {
+    const spec = asRecord(arg, '_merge');
+    const strategy = spec.strategy ?? 'keep';
+    if (strategy !== 'keep' && strategy !== 'overwrite') {
+      throw new TransformError('"_merge" "strategy" must be "keep" or "overwrite"');
+    }
+    const value = fromValue(evalExpr(spec.value, ctx));
+    if (value.kind !== 'object') throw new TransformError('"_merge" "value" must be an object');
+    if (working.root.kind !== 'object') {
+      throw new TransformError('"_merge" needs an object document');
+    }
+    mergeNode(working.root, value, strategy === 'overwrite');
+  },
+
+  /**
+   * Turn null-like strings (`tokens`, default `NULL_TOKENS`) into real nulls,
+   * under each of `paths` (missing ones are skipped) or across the whole
+   * document. Matching is exact; other values are left alone.
+   */
+  _nullify(working, arg) {
+    const spec = arg === null ? {} : asRecord(arg, '_nullify');
+    const tokens = spec.tokens ?? NULL_TOKENS;
+    if (!Array.isArray(tokens) || tokens.some((t) => typeof t !== 'string')) {
+      throw new TransformError('"_nullify" "tokens" must be a list of strings');
+    }
+    const match = new Set(tokens as string[]);
+    if (spec.paths === undefined) {
+      working.root = nullifyNode(working.root, match);
+      return;
+    }
+    if (!Array.isArray(spec.paths)) throw new TransformError('"_nullify" "paths" must be a list');
+    for (const path of spec.paths) {
+      if (typeof path !== 'string') throw new TransformError('"_nullify" paths must be strings');
+      const node = get(working, path);
+      if (node !== undefined) set(working, path, nullifyNode(node, match));
+    }
+  },
+
   /** Reshape: build a fresh document from only the named paths (strict projection). */
   _select(working, arg, ctx) {
     const entries = Object.entries(asRecord(arg, '_select')).map(
@@ -112,6 +233,55 @@ const STRUCTURAL: Record<string, StructuralOp> = {
     working.root = fresh.root;
   },
 
+  /** Reshape: keep only the listed paths, where they are, and drop everything else. */
+  _keep(working, arg) {
+    if (!Array.isArray(arg)) throw new TransformError('"_keep" expects a list of paths');
+    const fresh: Document = { root: { kind: 'object', fields: {} }, meta: working.meta };
+    for (const path of arg) {
+      if (typeof path !== 'string') throw new TransformError('"_keep" paths must be strings');
+      if (parsePath(path).some((segment) => typeof segment === 'number')) {
+        throw new TransformError(keepIndexMessage(path));
+      }
+      const node = get(working, path);
+      if (node !== undefined) set(fresh, path, node);
+    }
+    working.root = fresh.root;
+  },
+
+  /** Parse each listed JSON string into nested data. Missing and null values are skipped. */
+  _parseJson(working, arg) {
+    if (!Array.isArray(arg)) throw new TransformError('"_parseJson" expects a list of paths');
+    for (const path of arg) {
+      if (typeof path !== 'string') throw new TransformError('"_parseJson" paths must be strings');
+      const node = get(working, path);
+      if (node === undefined || (node.kind === 'scalar' && node.value === null)) continue;
+      if (node.kind !== 'scalar' || typeof node.value !== 'string') {
+        throw new TransformError(`"_parseJson" target "${path}" is not a string`);
+      }
+      let value: unknown;
+      try {
+        value = JSON.parse(node.value);
+      } catch (err) {
+        const reason = jsonSyntaxReason(err);
+        throw new TransformError(`"_parseJson" target "${path}" is not valid JSON: ${reason}`);
+      }
+      set(working, path, fromValue(value));
+    }
+  },
+
+  /** Replace each listed value with its JSON text, the inverse of `_parseJson`. */
+  _stringifyJson(working, arg) {
+    if (!Array.isArray(arg)) throw new TransformError('"_stringifyJson" expects a list of paths');
+    for (const path of arg) {
+      if (typeof path !== 'string') {
+        throw new TransformError('"_stringifyJson" paths must be strings');
+      }
+      const node = get(working, path);
+      if (node === undefined) continue;
+      set(working, path, { kind: 'scalar', value: JSON.stringify(toValue(node)) });
+    }
+  },
+
   /** Run `then` when `cond` is truthy, otherwise `else`. */
   _when(working, arg, ctx) {
     const spec = asRecord(arg, '_when');
@@ -125,6 +295,35 @@ const STRUCTURAL: Record<string, StructuralOp> = {
     runSteps(working, branch, ctx);
   },
 
+  /**
+   * Run `steps` against each object element of the array at `in`, in place.
+   * Inside the steps `$path` references resolve against the element. A missing
+   * array is a no-op; non-object elements pass through untouched.
+   */
+  _each(working, arg, ctx) {
+    const spec = asRecord(arg, '_each');
+    if (typeof spec.in !== 'string') throw new TransformError('"_each" needs an "in" path string');
+    if (!Array.isArray(spec.steps)) throw new TransformError('"_each" needs a "steps" list');
+    const target = get(working, spec.in);
+    if (target === undefined) return;
+    if (target.kind !== 'array') {
+      throw new TransformError(`"_each" target "${spec.in}" is not an array`);
+    }
+    target.items.forEach((item, index) => {
+      if (item.kind !== 'object') return;
+      const element: Document = { root: item, meta: working.meta };
+      try {
+        runSteps(element, spec.steps as Step[], { ...ctx, working: element });
+      } catch (err) {
+        const message = err instanceof Error ? err.message : String(err);
+        const inner = err instanceof TransformError ? err : undefined;
+        const reason = inner?.reason ?? message;
+        throw new TransformError(`${spec.in}[${index}]: ${message}`, inner?.frames, reason);
+      }
+      target.items[index] = element.root;
+    });
+  },
+
   /**
    * Escape hatch: run a custom function on a JSON value. Reads `from` (default
    * the whole document), calls the named function, and writes the JSON result
@@ -157,6 +356,9 @@ const STRUCTURAL: Record<string, StructuralOp> = {
   },
 };
 
+/** Every step operator the engine implements. */
+export const STEP_OPERATORS = Object.keys(STRUCTURAL);
+
 function runSteps(working: Document, steps: Step[], ctx: Ctx): void {
   steps.forEach((step, index) => {
     const keys = Object.keys(step);
@@ -170,11 +372,27 @@ function runSteps(working: Document, steps: Step[], ctx: Ctx): void {
       impl(working, step[op], ctx);
     } catch (err) {
       const message = err instanceof Error ? err.message : String(err);
-      throw new TransformError(`step ${index} (${op}): ${message}`);
+      const inner = err instanceof TransformError ? err : undefined;
+      const frames = [{ index, op, step }, ...(inner?.frames ?? [])];
+      const reason = inner?.reason ?? message;
+      throw new TransformError(`step ${index} (${op}): ${message}`, frames, reason);
     }
   });
 }
 
+/** Whether a document is empty output: an object with no fields. */
+export function isEmptyOutput(doc: Document): boolean {
+  return doc.root.kind === 'object' && Object.keys(doc.root.fields).length === 0;
+}
+
+/**
+ * Whether a caller should drop `out` rather than write it, under the flow's
+ * `onEmptyOutput` policy. (`error` never gets here: `applyFlow` throws.)
+ */
+export function skipsOutput(flow: Flow, out: Document): boolean {
+  return (flow.onEmptyOutput ?? 'skip') === 'skip' && isEmptyOutput(out);
+}
+
 /** Run a flow against a document, returning a new transformed document. */
 export function applyFlow(doc: Document, flow: Flow, options: RunOptions = {}): Document {
   const working: Document = {
@@ -182,5 +400,8 @@ export function applyFlow(doc: Document, flow: Flow, options: RunOptions = {}):
     meta: { ...doc.meta, errors: [...doc.meta.errors] },
   };
   runSteps(working, flow.steps, { working, functions: options.functions ?? {} });
+  if (flow.onEmptyOutput === 'error' && isEmptyOutput(working)) {
+    throw new TransformError('flow produced an empty document (onEmptyOutput: error)');
+  }
   return working;
 }
@@ -1,7 +1,21 @@
+/** One step a failure happened in: its position in its step list, its operator, and the step. */
+export interface StepFrame {
+  index: number;
+  op: string;
+  step: Record<string, unknown>;
+}
+
 /** Thrown when a step or expression is malformed or references a bad path. */
 export class TransformError extends Error {
-  constructor(message: string) {
+  /** The steps the failure happened in, outermost first; empty outside a step. */
+  frames: StepFrame[];
+  /** The failure itself, without the `step N (op):` prefixes of `message`. */
+  reason: string;
+
+  constructor(message: string, frames: StepFrame[] = [], reason = message) {
     super(message);
     this.name = 'TransformError';
+    this.frames = frames;
+    this.reason = reason;
   }
 }
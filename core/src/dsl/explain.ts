@@ -0,0 +1,237 @@
+/**
+ * Plain-language narration of a flow (`weavster explain`).
+ *
+ * Each step becomes one numbered sentence; `_when` branches nest under their
+ * step. Expressions render compactly in DSL terms (`$path`, `_op(args)`), so the
+ * narration stays faithful to the YAML rather than paraphrasing it.
+ * `diagramFlow` draws the same walk as a top-to-bottom ASCII diagram.
+ */
+import { type Flow, NULL_TOKENS, type Step } from './engine.js';
+
+export type ExplainFormat = 'text' | 'markdown';
+
+const INDENT = '   ';
+
+/** Render an expression compactly: `$path`, `"literal"`, `_op(args)`. */
+export function describeExpr(expr: unknown): string {
+  if (typeof expr === 'string') {
+    if (expr.startsWith('$$')) return JSON.stringify(expr.slice(1));
+    if (expr.startsWith('$')) return expr;
+    return JSON.stringify(expr);
+  }
+  if (Array.isArray(expr)) return `[${expr.map(describeExpr).join(', ')}]`;
+  if (expr !== null && typeof expr === 'object') {
+    const entries = Object.entries(expr as Record<string, unknown>);
+    if (entries.length === 1 && entries[0][0].startsWith('_')) {
+      const [op, arg] = entries[0];
+      if (op === '_lit') return JSON.stringify(arg);
+      return `${op}(${describeArgs(arg)})`;
+    }
+    return `{ ${entries.map(([k, v]) => `${k}: ${describeExpr(v)}`).join(', ')} }`;
+  }
+  return JSON.stringify(expr);
+}
+
+function describeArgs(arg: unknown): string {
+  if (Array.isArray(arg)) return arg.map(describeExpr).join(', ');
+  if (arg !== null && typeof arg === 'object') {
+    return Object.entries(arg as Record<string, unknown>)
+      .map(([k, v]) => `${k}: ${describeExpr(v)}`)
+      .join(', ');
+  }
+  return describeExpr(arg);
+}
+
+/** Join clauses as "a", "a and b", "a, b and c". */
+function and(parts: string[]): string {
+  if (parts.length <= 1) return parts.join('');
+  return `${parts.slice(0, -1).join(', ')} and ${parts[parts.length - 1]}`;
+}
+
+const entriesOf = (arg: unknown): [string, unknown][] =>
+  arg !== null && typeof arg === 'object' && !Array.isArray(arg)
+    ? Object.entries(arg as Record<string, unknown>)
+    : [];
+
+function explainStep(step: Step, depth: number, code: (s: string) => string): string[] {
+  const pad = INDENT.repeat(depth);
+  const [op] = Object.keys(step);
+  const arg = step[op];
+  const spec = Object.fromEntries(entriesOf(arg));
+  const sentence = (text: string) => [`${pad}${text}`];
+
+  switch (op) {
+    case '_set':
+      return sentence(
+        `Set ${and(entriesOf(arg).map(([p, e]) => `${code(p)} to ${code(describeExpr(e))}`))}`,
+      );
+    case '_default':
+      return sentence(
+        `Default ${and(entriesOf(arg).map(([p, e]) => `${code(p)} to ${code(describeExpr(e))}`))} where absent`,
+      );
+    case '_unset': {
+      const paths = Array.isArray(arg) ? arg.map(String) : [];
+      const noun = paths.length === 1 ? 'field' : 'fields';
+      return sentence(`Remove ${paths.length} ${noun}: ${paths.map(code).join(', ')}`);
+    }
+    case '_rename':
+      return sentence(
+        `Rename ${and(entriesOf(arg).map(([from, to]) => `${code(from)} to ${code(String(to))}`))}`,
+      );
+    case '_append':
+      return sentence(
+        `Append ${code(describeExpr(spec.value))} to the array at ${code(String(spec.to))}`,
+      );
+    case '_merge': {
+      const wins = spec.strategy === 'overwrite' ? 'it wins' : 'the document wins';
+      return sentence(
+        `Deep-merge ${code(describeExpr(spec.value))} into the document (on conflict ${wins})`,
+      );
+    }
+    case '_nullify': {
+      const tokens = Array.isArray(spec.tokens) ? spec.tokens : NULL_TOKENS;
+      const where = Array.isArray(spec.paths)
+        ? `under ${spec.paths.map((p) => code(String(p))).join(', ')}`
+        : 'anywhere in the document';
+      const list = tokens.map((t) => code(JSON.stringify(t))).join(', ');
+      return sentence(`Replace the strings ${list} with null ${where}`);
+    }
+    case '_select':
+      return sentence(
+        `Reshape the document to only ${and(entriesOf(arg).map(([p, e]) => `${code(p)} = ${code(describeExpr(e))}`))}`,
+      );
+    case '_keep': {
+      const paths = Array.isArray(arg) ? arg.map(String) : [];
+      return sentence(`Keep only ${paths.map(code).join(', ')}, dropping every other field`);
+    }
+    case '_parseJson': {
+      const paths = Array.isArray(arg) ? arg.map(String) : [];
+      return sentence(`Parse the JSON text in ${and(paths.map(code))} into nested data`);
+    }
+    case '_stringifyJson': {
+      const paths = Array.isArray(arg) ? arg.map(String) : [];
+      const its = paths.length === 1 ? 'its' : 'their';
+      return sentence(`Replace ${and(paths.map(code))} with ${its} JSON text`);
+    }
+    case '_when': {
+      const lines = sentence(`When ${code(describeExpr(spec.cond))}:`);
+      lines.push(...explainSteps(spec.then as Step[], depth + 1, code));
+      if (Array.isArray(spec.else)) {
+        lines.push(`${pad}${INDENT}Otherwise:`);
+        lines.push(...explainSteps(spec.else as Step[], depth + 1, code));
+      }
+      return lines;
+    }
+    case '_each': {
+      const lines = sentence(`For each element of ${code(String(spec.in))}:`);
+      lines.push(...explainSteps(spec.steps as Step[], depth + 1, code));
+      return lines;
+    }
+    case '_ts': {
+      const from = spec.from === undefined ? 'the whole document' : code(String(spec.from));
+      const to =
+        spec.to === undefined ? 'replacing the document' : `writing ${code(String(spec.to))}`;
+      return sentence(`Run the custom function ${code(String(spec.module))} on ${from}, ${to}`);
+    }
+    default:
+      return sentence(`Run the unknown operator ${code(op)}`);
+  }
+}
+
+function explainSteps(steps: Step[], depth: number, code: (s: string) => string): string[] {
+  const pad = INDENT.repeat(depth);
+  return steps.flatMap((step, index) => {
+    const [first, ...rest] = explainStep(step, depth, code);
+    return [`${pad}${index + 1}. ${first.slice(pad.length)}`, ...rest];
+  });
+}
+
+/**
+ * Narrate a flow as numbered steps. `markdown` wraps paths and expressions in
+ * code spans so the output pastes cleanly into a PR; `text` leaves them bare.
+ */
+export function explainFlow(flow: Flow, format: ExplainFormat = 'text'): string {
+  const code = format === 'markdown' ? (s: string) => `\`${s}\`` : (s: string) => s;
+  return explainSteps(flow.steps, 0, code).join('\n');
+}
+
+/** `1 step`, `3 steps`: the size of a nested step list. */
+function stepCount(list: unknown): string {
+  const n = Array.isArray(list) ? list.length : 0;
+  return `${n} step${n === 1 ? '' : 's'}`;
+}
+
+/** A step's operator and its key config, on one line: `_set id, name`. */
+function summarizeStep(step: Step): string {
+  const [op] = Object.keys(step);
+  const arg = step[op];
+  const spec = Object.fromEntries(entriesOf(arg));
+  const paths = () => (Array.isArray(arg) ? arg.map(String).join(', ') : '');
+  const config = (() => {
+    switch (op) {
+      case '_set':
+      case '_default':
+      case '_select':
+        return entriesOf(arg)
+          .map(([path]) => path)
+          .join(', ');
+      case '_unset':
+      case '_keep':
+      case '_parseJson':
+      case '_stringifyJson':
+        return paths();
+      case '_rename':
+        return entriesOf(arg)
+          .map(([from, to]) => `${from} -> ${String(to)}`)
+          .join(', ');
+      case '_append':
+        return `to ${String(spec.to)}`;
+      case '_merge': {
+        const wins = spec.strategy === 'overwrite' ? 'it wins' : 'the document wins';
+        return `${describeExpr(spec.value)} (on conflict ${wins})`;
+      }
+      case '_nullify':
+        return Array.isArray(spec.paths) ? spec.paths.map(String).join(', ') : 'whole document';
+      case '_when': {
+        const branches = [`then ${stepCount(spec.then)}`];
+        if (Array.isArray(spec.else)) branches.push(`else ${stepCount(spec.else)}`);
+        return `${describeExpr(spec.cond)} (${branches.join(', ')})`;
+      }
+      case '_each':
+        return `${String(spec.in)} (${stepCount(spec.steps)})`;
+      case '_ts': {
+        const from = spec.from === undefined ? 'document' : String(spec.from);
+        const to = spec.to === undefined ? 'document' : String(spec.to);
+        return `${String(spec.module)} (${from} -> ${to})`;
+      }
+      default:
+        return '';
+    }
+  })();
+  return config === '' ? op : `${op} ${config}`;
+}
+
+/** The ends of a diagram: one line per source and per sink feeding the flow. */
+export interface DiagramEnds {
+  inputs?: string[];
+  outputs?: string[];
+}
+
+/**
+ * Draw a flow as an ASCII diagram, top to bottom: its inputs, each top-level
+ * step with its key config, then its outputs, joined by arrows. A failed step
+ * always fails the document, so every step carries that annotation; the
+ * outputs carry the flow's `onEmptyOutput` policy.
+ */
+export function diagramFlow(flow: Flow, ends: DiagramEnds = {}): string {
+  const arrow = ['  |', '  v'];
+  const inputs = ends.inputs?.length ? ends.inputs : ['input'];
+  const outputs = ends.outputs?.length ? ends.outputs : ['output'];
+  const empty = `on empty output: ${flow.onEmptyOutput ?? 'skip'}`;
+  const lines = [...inputs];
+  flow.steps.forEach((step, index) => {
+    lines.push(...arrow, `${index + 1}. ${summarizeStep(step)}  [on error: fail the document]`);
+  });
+  lines.push(...arrow, ...outputs.map((output) => `${output}  [${empty}]`));
+  return lines.join('\n');
+}
@@ -13,6 +13,7 @@
 import type { Document } from '../model.js';
 import { getValue } from '../path.js';
 import { TransformError } from './errors.js';
+import { uuid5, uuidNamespace } from './uuid.js';
 
 /**
  * A custom transform function (the TypeScript escape hatch). The contract is
@@ -100,6 +101,22 @@ export const VALUE_OPS: Record<string, ValueOp> = {
     const o = record(arg, '_cond');
     return evalExpr(o.if, ctx) ? evalExpr(o.then, ctx) : evalExpr(o.else, ctx);
   },
+  /**
+   * A deterministic (version 5) UUID from a namespace and a name, so the same
+   * input always gets the same id. Non-string names hash as their JSON text.
+   */
+  _uuid5(arg, ctx) {
+    const [ns, expr] = pair(arg, '_uuid5');
+    const namespace = uuidNamespace(toStr(evalExpr(ns, ctx)));
+    if (namespace === undefined) {
+      throw new TransformError('"_uuid5" namespace must be a UUID or one of dns, url, oid, x500');
+    }
+    const name = evalExpr(expr, ctx);
+    if (name === null || name === undefined) {
+      throw new TransformError('"_uuid5" has no name to hash (the value is missing or null)');
+    }
+    return uuid5(namespace, typeof name === 'string' ? name : JSON.stringify(name));
+  },
 };
 
 function isOperator(keys: string[]): boolean {
@@ -0,0 +1,119 @@
+/**
+ * Name-based UUIDs (RFC 9562 version 5: SHA-1 of a namespace UUID and a name).
+ *
+ * Flows run inside a Javy wasm module with no `crypto`, so SHA-1 is done here
+ * in plain TypeScript. It hashes names of a few bytes, once per document, so
+ * the straightforward implementation is plenty.
+ */
+
+/** The namespaces RFC 9562 predefines, by their usual short names. */
+export const UUID_NAMESPACES: Record<string, string> = {
+  dns: '6ba7b810-9dad-11d1-80b4-00c04fd430c8',
+  url: '6ba7b811-9dad-11d1-80b4-00c04fd430c8',
+  oid: '6ba7b812-9dad-11d1-80b4-00c04fd430c8',
+  x500: '6ba7b814-9dad-11d1-80b4-00c04fd430c8',
+};
+
+const UUID = /^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/i;
+
+/** UTF-8 bytes of a string (no `TextEncoder` to lean on inside the wasm module). */
+function utf8(text: string): number[] {
+  const bytes: number[] = [];
+  for (const char of text) {
+    const code = char.codePointAt(0) as number;
+    if (code < 0x80) {
+      bytes.push(code);
+    } else if (code < 0x800) {
+      bytes.push(0xc0 | (code >> 6), 0x80 | (code & 0x3f));
+    } else if (code < 0x10000) {
+      bytes.push(0xe0 | (code >> 12), 0x80 | ((code >> 6) & 0x3f), 0x80 | (code & 0x3f));
+    } else {
+      bytes.push(
+        0xf0 | (code >> 18),
+        0x80 | ((code >> 12) & 0x3f),
+        0x80 | ((code >> 6) & 0x3f),
+        0x80 | (code & 0x3f),
+      );
+    }
+  }
+  return bytes;
+}
+
+/** The four bytes of a 32-bit word, most significant first. */
+const bigEndian = (word: number): number[] => [
+  word >>> 24,
+  (word >>> 16) & 0xff,
+  (word >>> 8) & 0xff,
+  word & 0xff,
+];
+
+/** SHA-1 digest (FIPS 180-4) of a byte array. */
+function sha1(message: number[]): number[] {
+  const bytes = [...message, 0x80];
+  while (bytes.length % 64 !== 56) bytes.push(0);
+  // Bit length as a 64-bit big-endian integer; names never reach 2^32 bits.
+  const bits = message.length * 8;
+  bytes.push(0, 0, 0, 0, ...bigEndian(bits));
+
+  const h = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+  const w = new Array<number>(80);
+  const rotl = (x: number, n: number) => (x << n) | (x >>> (32 - n));
+  for (let block = 0; block < bytes.length; block += 64) {
+    for (let i = 0; i < 16; i++) {
+      const at = block + i * 4;
+      w[i] = (bytes[at] << 24) | (bytes[at + 1] << 16) | (bytes[at + 2] << 8) | bytes[at + 3];
+    }
+    for (let i = 16; i < 80; i++) w[i] = rotl(w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16], 1);
+
+    let [a, b, c, d, e] = h;
+    for (let i = 0; i < 80; i++) {
+      let f: number;
+      let k: number;
+      if (i < 20) {
+        f = (b & c) | (~b & d);
+        k = 0x5a827999;
+      } else if (i < 40) {
+        f = b ^ c ^ d;
+        k = 0x6ed9eba1;
+      } else if (i < 60) {
+        f = (b & c) | (b & d) | (c & d);
+        k = 0x8f1bbcdc;
+      } else {
+        f = b ^ c ^ d;
+        k = 0xca62c1d6;
+      }
+      const t = (rotl(a, 5) + f + e + k + w[i]) | 0;
+      e = d;
+      d = c;
+      c = rotl(b, 30);
+      b = a;
+      a = t;
+    }
+    h[0] = (h[0] + a) | 0;
+    h[1] = (h[1] + b) | 0;
+    h[2] = (h[2] + c) | 0;
+    h[3] = (h[3] + d) | 0;
+    h[4] = (h[4] + e) | 0;
+  }
+  return h.flatMap(bigEndian);
+}
+
+/** Resolve a namespace argument: a UUID, or one of the predefined short names. */
+export function uuidNamespace(namespace: string): string | undefined {
+  const uuid = UUID_NAMESPACES[namespace.toLowerCase()] ?? namespace;
+  return UUID.test(uuid) ? uuid.toLowerCase() : undefined;
+}
+
+/** The version-5 UUID for `name` within `namespace` (a UUID string). */
+export function uuid5(namespace: string, name: string): string {
+  const ns = namespace.replace(/-/g, '');
+  const nsBytes = Array.from({ length: 16 }, (_, i) =>
+    Number.parseInt(ns.slice(i * 2, i * 2 + 2), 16),
+  );
+  const digest = sha1([...nsBytes, ...utf8(name)]).slice(0, 16);
+  digest[6] = (digest[6] & 0x0f) | 0x50;
+  digest[8] = (digest[8] & 0x3f) | 0x80;
+  const hex = digest.map((byte) => byte.toString(16).padStart(2, '0')).join('');
+  const groups = [hex.slice(0, 8), hex.slice(8, 12), hex.slice(12, 16), hex.slice(16, 20)];
+  return [...groups, hex.slice(20)].join('-');
+}
@@ -1,5 +1,9 @@
 export * from './model.js';
 export * from './path.js';
 export * from './dsl/engine.js';
+export * from './dsl/explain.js';
+export * from './dsl/check.js';
+export * from './dsl/catalog.js';
+export * from './sim.js';
 export * as json from './formats/json.js';
 export * as xml from './formats/xml.js';
@@ -4,31 +4,83 @@
  * The canonical form of a path is a segment array: string segments address
  * object fields, number segments address array indices. The string form is
  * dotted with bracketed indices — `lines[0].sku` ⇄ `['lines', 0, 'sku']` —
- * and is what the transform DSL will accept from authors.
+ * and is what the transform DSL accepts from authors. A key that contains
+ * `.`, `[`, or `]` (or is empty) is written as a quoted bracket segment with
+ * JSON string escapes: `headers["content.type"]` ⇄ `['headers', 'content.type']`.
+ *
+ * This is the one path grammar: every step, `$path` reference, and validate-time
+ * check goes through `parsePath`.
  */
 import { type Document, type Node, toValue } from './model.js';
 
 export type Segment = string | number;
 
-const TOKEN = /\[(\d+)\]|([^.[\]]+)/g;
+/** A bare key: one or more characters other than the separators. */
+const KEY = /[^.[\]]+/y;
+const INDEX = /\[(\d+)\]/y;
+/** A quoted key: a JSON string literal in brackets. */
+const QUOTED = /\[("(?:[^"\\]|\\.)*")\]/y;
+
+/** Whether a key can be written bare (otherwise it needs `["..."]`). */
+const isBare = (key: string): boolean => /^[^.[\]]+$/.test(key);
 
-/** Parse a dotted/bracketed path string into segments. `''` is the root. */
+function match(re: RegExp, path: string, at: number): RegExpExecArray | null {
+  re.lastIndex = at;
+  return re.exec(path);
+}
+
+/**
+ * Parse a path string into segments. `''` is the root. A malformed path (an
+ * empty segment, an unclosed or non-numeric bracket, a bad escape in a quoted
+ * key) is a `PathError`.
+ */
 export function parsePath(path: string): Segment[] {
   if (path === '') return [];
+  const invalid = (why: string, at: number) =>
+    new PathError(`invalid path "${path}": ${why} at character ${at + 1}`);
   const segments: Segment[] = [];
-  for (const match of path.matchAll(TOKEN)) {
-    const [, index, key] = match;
-    segments.push(index !== undefined ? Number(index) : key);
+  let at = 0;
+  while (at < path.length) {
+    if (path[at] === '[') {
+      const index = match(INDEX, path, at);
+      if (index !== null) {
+        segments.push(Number(index[1]));
+        at = INDEX.lastIndex;
+        continue;
+      }
+      const quoted = match(QUOTED, path, at);
+      if (quoted === null) throw invalid('expected [<index>] or ["<key>"]', at);
+      // The pattern only finds the closing quote; the escapes and characters
+      // inside are JSON's to judge.
+      try {
+        segments.push(JSON.parse(quoted[1]) as string);
+      } catch {
+        throw invalid('quoted key is not a valid JSON string', at);
+      }
+      at = QUOTED.lastIndex;
+      continue;
+    }
+    // A key starts the path or follows a dot; a dot needs a segment on each side.
+    if (segments.length > 0) {
+      if (path[at] !== '.') throw invalid(`unexpected "${path[at]}"`, at);
+      at += 1;
+    }
+    const key = match(KEY, path, at);
+    if (key === null) throw invalid('empty segment', at);
+    segments.push(key[0]);
+    at = KEY.lastIndex;
   }
   return segments;
 }
 
-/** Render segments back into a dotted/bracketed path string. */
+/** Render segments back into a path string; the inverse of `parsePath`. */
 export function formatPath(segments: Segment[]): string {
   let out = '';
   for (const segment of segments) {
     if (typeof segment === 'number') {
       out += `[${segment}]`;
+    } else if (!isBare(segment)) {
+      out += `[${JSON.stringify(segment)}]`;
     } else {
       out += out === '' ? segment : `.${segment}`;
     }
@@ -0,0 +1,160 @@
+/**
+ * Synthetic documents for load simulation (`weavster flow simulate`).
+ *
+ * A spec names a generator per field; a simulator built from it yields one
+ * plain JSON value per call. Every random choice comes from a seeded PRNG, so
+ * the same spec and seed yield the same documents on every run.
+ */
+
+export type FieldSpec =
+  | { type: 'uuid' }
+  | { type: 'name' }
+  | { type: 'enum'; values: unknown[]; weights?: number[] }
+  | { type: 'int'; min: number; max: number }
+  | { type: 'timestamp'; start?: string; stepMs?: number; jitterMs?: number }
+  | { type: 'object'; fields: Record<string, FieldSpec> };
+
+export interface SimSpec {
+  /** Seed for every generator; a caller's seed takes precedence. */
+  seed?: number;
+  fields: Record<string, FieldSpec>;
+}
+
+export interface SimulatorOptions {
+  seed?: number;
+  /** Base time for a `timestamp` without `start`, in ms since the epoch. */
+  now?: number;
+}
+
+/** Thrown when a spec is well-formed but cannot generate (an empty enum, `min > max`). */
+export class SimSpecError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'SimSpecError';
+  }
+}
+
+const FIRST_NAMES = ['Ada', 'Alan', 'Barbara', 'Dennis', 'Edsger', 'Frances', 'Grace', 'Ken'];
+const LAST_NAMES = ['Allen', 'Dijkstra', 'Hopper', 'Liskov', 'Lovelace', 'Ritchie', 'Turing'];
+
+/** mulberry32: a small, fast PRNG with a 32-bit state; uniform floats in [0, 1). */
+export function seededRandom(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) | 0;
+    let t = Math.imul(state ^ (state >>> 15), 1 | state);
+    t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+type Generate = (index: number) => unknown;
+
+/**
+ * Build a simulator for `spec`: each call returns the next document. Throws
+ * `SimSpecError`, naming the field, for a spec that cannot generate.
+ */
+export function createSimulator(spec: SimSpec, options: SimulatorOptions = {}): () => unknown {
+  const random = seededRandom(options.seed ?? spec.seed ?? 0);
+  const now = options.now ?? Date.now();
+  const root = objectGenerator(spec.fields, '', random, now);
+  let index = 0;
+  return () => root(index++);
+}
+
+function objectGenerator(
+  fields: Record<string, FieldSpec>,
+  prefix: string,
+  random: () => number,
+  now: number,
+): Generate {
+  const generators = Object.entries(fields).map(
+    ([key, field]) => [key, fieldGenerator(field, `${prefix}${key}`, random, now)] as const,
+  );
+  return (index) => {
+    const out: Record<string, unknown> = {};
+    for (const [key, generate] of generators) out[key] = generate(index);
+    return out;
+  };
+}
+
+function fieldGenerator(
+  field: FieldSpec,
+  path: string,
+  random: () => number,
+  now: number,
+): Generate {
+  switch (field.type) {
+    case 'uuid':
+      return () => uuid4(random);
+    case 'name':
+      return () => `${pick(FIRST_NAMES, random)} ${pick(LAST_NAMES, random)}`;
+    case 'enum':
+      return enumGenerator(field.values, field.weights, path, random);
+    case 'int': {
+      const { min, max } = field;
+      if (!Number.isInteger(min) || !Number.isInteger(max) || min > max) {
+        throw new SimSpecError(`${path}: int needs integers with min <= max, got ${min}..${max}`);
+      }
+      return () => min + Math.floor(random() * (max - min + 1));
+    }
+    case 'timestamp': {
+      const start = field.start === undefined ? now : Date.parse(field.start);
+      if (Number.isNaN(start)) {
+        throw new SimSpecError(`${path}: timestamp start "${field.start}" is not an ISO date`);
+      }
+      const step = field.stepMs ?? 0;
+      const jitter = field.jitterMs ?? 0;
+      return (index) => {
+        const offset = Math.round((random() * 2 - 1) * jitter);
+        return new Date(start + index * step + offset).toISOString();
+      };
+    }
+    case 'object':
+      return objectGenerator(field.fields, `${path}.`, random, now);
+  }
+}
+
+function enumGenerator(
+  values: unknown[],
+  weights: number[] | undefined,
+  path: string,
+  random: () => number,
+): Generate {
+  if (values.length === 0) throw new SimSpecError(`${path}: enum has no values`);
+  if (weights === undefined) return () => pick(values, random);
+  if (weights.length !== values.length) {
+    throw new SimSpecError(
+      `${path}: enum has ${values.length} values but ${weights.length} weights`,
+    );
+  }
+  const total = weights.reduce((sum, weight) => sum + weight, 0);
+  if (!(total > 0) || weights.some((weight) => weight < 0)) {
+    throw new SimSpecError(`${path}: enum weights must be non-negative with a positive sum`);
+  }
+  // Float rounding can leave a roll a hair past the last bucket; it lands in
+  // the last value that can be drawn at all.
+  let last = weights.length - 1;
+  while (weights[last] === 0) last -= 1;
+  return () => {
+    let roll = random() * total;
+    for (let i = 0; i < last; i++) {
+      roll -= weights[i];
+      if (roll < 0) return values[i];
+    }
+    return values[last];
+  };
+}
+
+const pick = <T>(items: T[], random: () => number): T =>
+  items[Math.floor(random() * items.length)];
+
+/** A random (version 4) UUID, drawn from the seeded PRNG rather than `crypto`. */
+function uuid4(random: () => number): string {
+  const bytes = Array.from({ length: 16 }, () => Math.floor(random() * 256));
+  bytes[6] = (bytes[6] & 0x0f) | 0x40;
+  bytes[8] = (bytes[8] & 0x3f) | 0x80;
+  const hex = bytes.map((byte) => byte.toString(16).padStart(2, '0')).join('');
+  const groups = [hex.slice(0, 8), hex.slice(8, 12), hex.slice(12, 16), hex.slice(16, 20)];
+  return [...groups, hex.slice(20)].join('-');
+}
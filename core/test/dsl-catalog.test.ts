@@ -0,0 +1,42 @@
+import { describe, expect, it } from 'vitest';
+import { OPERATORS, describeOperators, suggestOperator } from '../src/dsl/catalog.js';
+import { STEP_OPERATORS } from '../src/dsl/engine.js';
+import { VALUE_OPS } from '../src/dsl/expr.js';
+
+const names = (kind: string) =>
+  OPERATORS.filter((op) => op.kind === kind)
+    .map((op) => op.name)
+    .sort();
+
+describe('OPERATORS', () => {
+  it('has exactly one entry for every operator the engine implements', () => {
+    expect(names('step')).toEqual([...STEP_OPERATORS].sort());
+    expect(names('value')).toEqual(Object.keys(VALUE_OPS).sort());
+  });
+});
+
+describe('describeOperators', () => {
+  it('renders a markdown table per kind', () => {
+    const markdown = describeOperators('markdown');
+    expect(markdown).toMatch(/^### Steps\n\n\| Operator \| Shape \| Does \|\n\| --- /);
+    expect(markdown).toContain('\n### Value operators\n');
+    expect(markdown).toContain('| `_set` | `{ <path>: <expr>, ... }` | set each path |');
+    // The `|` in `_merge`'s shape is escaped so it stays in its cell.
+    expect(markdown).toContain('strategy?: keep\\|overwrite');
+  });
+
+  it('aligns the text columns and lists the JSON entries as-is', () => {
+    const text = describeOperators('text').split('\n');
+    expect(text[0]).toBe('Steps');
+    expect(text[1]).toMatch(/^ {2}_set {12}\{ <path>/);
+    expect(JSON.parse(describeOperators('json'))).toEqual(OPERATORS);
+  });
+});
+
+describe('suggestOperator', () => {
+  it('finds a close operator of the same kind, and nothing for a distant name', () => {
+    expect(suggestOperator('_uper', 'value')).toBe('_upper');
+    expect(suggestOperator('_rename_', 'step')).toBe('_rename');
+    expect(suggestOperator('_lookup', 'value')).toBeUndefined();
+  });
+});
@@ -0,0 +1,162 @@
+import { describe, expect, it } from 'vitest';
+import { document, fromValue, toValue } from '../src/model.js';
+import { checkOperators, checkPaths, lintFlow } from '../src/dsl/check.js';
+import { type Flow, applyFlow } from '../src/dsl/engine.js';
+
+describe('checkPaths', () => {
+  it('accepts a flow whose paths all parse', () => {
+    const flow: Flow = {
+      steps: [
+        { _set: { 'headers["content.type"]': '$raw["content.type"]', id: { _upper: '$id' } } },
+        { _rename: { 'order.@id': 'id' } },
+        { _each: { in: 'lines', steps: [{ _unset: ['lines[0].sku'] }] } },
+        { _set: { note: { _lit: '$not..a.path' }, price: '$$5' } },
+      ],
+    };
+    expect(checkPaths(flow)).toEqual([]);
+  });
+
+  it('reports a malformed step path and $path reference with their step', () => {
+    const flow: Flow = {
+      steps: [
+        { _set: { 'a..b': 1 } },
+        { _when: { cond: '$x[', then: [{ _unset: ['ok', 'bad.'] }] } },
+      ],
+    };
+    expect(checkPaths(flow)).toEqual([
+      'step 0 (_set): invalid path "a..b": empty segment at character 3',
+      'step 1 (_when): invalid path "x[": expected [<index>] or ["<key>"] at character 2',
+      'step 1 (_when): step 0 (_unset): invalid path "bad.": empty segment at character 5',
+    ]);
+  });
+
+  it('covers rename targets, append/ts/each paths, and merge values', () => {
+    const flow: Flow = {
+      steps: [
+        { _rename: { a: 'b..c' } },
+        { _append: { to: 'x[y]', value: 1 } },
+        { _ts: { module: 'm', from: '.a' } },
+        { _each: { in: 'a]', steps: [] } },
+        { _merge: { value: { k: '$q..r' } } },
+      ],
+    };
+    expect(checkPaths(flow).map((e) => e.split(':')[0])).toEqual([
+      'step 0 (_rename)',
+      'step 1 (_append)',
+      'step 2 (_ts)',
+      'step 3 (_each)',
+      'step 4 (_merge)',
+    ]);
+  });
+
+  it('reports a _keep path that indexes into an array', () => {
+    const flow: Flow = {
+      steps: [{ _when: { cond: true, then: [{ _keep: ['id', 'lines[0]', 'lines[1].sku'] }] } }],
+    };
+    expect(checkPaths(flow)).toEqual([
+      'step 0 (_when): step 0 (_keep): "_keep" path "lines[0]" has an array index; ' +
+        '_keep keeps whole arrays, so list the array itself',
+      'step 0 (_when): step 0 (_keep): "_keep" path "lines[1].sku" has an array index; ' +
+        '_keep keeps whole arrays, so list the array itself',
+    ]);
+  });
+});
+
+describe('checkOperators', () => {
+  it('accepts known operators and leaves _lit arguments alone', () => {
+    const flow: Flow = {
+      steps: [
+        { _set: { name: { _upper: { _concat: ['$a', '$b'] } }, raw: { _lit: { _nope: 1 } } } },
+        { _when: { cond: { _and: [{ _exists: '$a' }] }, then: [] } },
+      ],
+    };
+    expect(checkOperators(flow)).toEqual([]);
+  });
+
+  it('reports an operator no document has reached yet, with its step', () => {
+    const flow: Flow = {
+      steps: [
+        { _set: { region: { _lookup: { table: 'regions', key: '$zip' } } } },
+        {
+          _when: {
+            cond: { _eq: ['$kind', 'x'] },
+            then: [{ _append: { to: 'tags', value: { _slug: '$name' } } }],
+          },
+        },
+      ],
+    };
+    expect(checkOperators(flow)).toEqual([
+      'step 0 (_set): unknown operator "_lookup"',
+      'step 1 (_when): step 0 (_append): unknown operator "_slug"',
+    ]);
+  });
+
+  it('suggests the operator a misspelling was probably meant to be', () => {
+    const flow: Flow = { steps: [{ _set: { id: { _uper: '$id' }, at: { _toISO: '$at' } } }] };
+    expect(checkOperators(flow)).toEqual([
+      'step 0 (_set): unknown operator "_uper" (did you mean "_upper"?)',
+      'step 0 (_set): unknown operator "_toISO" (did you mean "_toIso"?)',
+    ]);
+  });
+});
+
+describe('quoted keys at run time', () => {
+  it('reads and writes a key containing a dot without splitting it', () => {
+    const doc = document(fromValue({ raw: { 'content.type': 'json' } }), { sourceFormat: 'json' });
+    const steps = [{ _set: { 'meta["content.type"]': '$raw["content.type"]' } }];
+    const out = applyFlow(doc, { steps });
+    expect(toValue(out.root)).toEqual({
+      raw: { 'content.type': 'json' },
+      meta: { 'content.type': 'json' },
+    });
+  });
+});
+
+describe('lintFlow', () => {
+  it('has nothing to say about an ordinary flow', () => {
+    const flow: Flow = {
+      steps: [
+        { _set: { id: '$orderId', note: '$$id' } },
+        { _unset: ['internal'] },
+        { _when: { cond: { _eq: ['$status', 'new'] }, then: [{ _set: { priority: 'high' } }] } },
+      ],
+    };
+    expect(lintFlow(flow)).toEqual([]);
+  });
+
+  it('flags a field set to itself, however the path is spelled', () => {
+    const flow: Flow = {
+      steps: [
+        { _set: { id: '$id', total: '$sum' } },
+        { _each: { in: 'lines', steps: [{ _set: { 'a.b': '$a["b"]' } }] } },
+      ],
+    };
+    expect(lintFlow(flow)).toEqual([
+      'step 0 (_set): sets "id" to itself ($id); this does nothing',
+      'step 1 (_each): step 0 (_set): sets "a.b" to itself ($a["b"]); this does nothing',
+    ]);
+  });
+
+  it('flags a condition that reads nothing, so one branch is dead', () => {
+    const flow: Flow = {
+      steps: [
+        { _when: { cond: true, then: [], else: [{ _set: { x: 1 } }] } },
+        { _when: { cond: { _eq: [1, 2] }, then: [{ _set: { x: 2 } }] } },
+      ],
+    };
+    expect(lintFlow(flow)).toEqual([
+      'step 0 (_when): condition is always true, so "then" always runs',
+      'step 1 (_when): condition is always false, so "then" never runs',
+    ]);
+  });
+
+  it('flags a self-rename and an unset the next step undoes', () => {
+    const flow: Flow = {
+      steps: [{ _rename: { sku: 'sku' } }, { _unset: ['tmp', 'keep'] }, { _set: { tmp: 1 } }],
+    };
+    expect(lintFlow(flow)).toEqual([
+      'step 0 (_rename): renames "sku" to itself; this does nothing',
+      'step 1 (_unset): removes "tmp", which step 2 (_set) sets again',
+    ]);
+  });
+});
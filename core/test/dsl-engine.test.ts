@@ -1,6 +1,6 @@
 import { describe, expect, it } from 'vitest';
 import { document, fromValue, toValue } from '../src/model.js';
-import { type Flow, applyFlow } from '../src/dsl/engine.js';
+import { type Flow, TransformError, applyFlow, skipsOutput } from '../src/dsl/engine.js';
 
 const docOf = (value: unknown) => document(fromValue(value), { sourceFormat: 'json' });
 const run = (value: unknown, steps: Flow['steps']) =>
@@ -80,6 +80,78 @@ describe('_append', () => {
   });
 });
 
+describe('_nullify', () => {
+  it('turns "N/A" and "" into null anywhere and leaves real values intact', () => {
+    const doc = { id: 'A-1', note: 'N/A', qty: 0, lines: [{ sku: '' }, { sku: 'W' }], ok: false };
+    expect(run(doc, [{ _nullify: null }])).toEqual({
+      id: 'A-1',
+      note: null,
+      qty: 0,
+      lines: [{ sku: null }, { sku: 'W' }],
+      ok: false,
+    });
+  });
+
+  it('limits itself to the named paths and tokens', () => {
+    const doc = { a: 'N/A', b: { c: '-', d: 'N/A' } };
+    expect(run(doc, [{ _nullify: { paths: ['b', 'missing'], tokens: ['-'] } }])).toEqual({
+      a: 'N/A',
+      b: { c: null, d: 'N/A' },
+    });
+  });
+
+  it('rejects non-string tokens', () => {
+    expect(() => run({}, [{ _nullify: { tokens: [0] } }])).toThrow(
+      'step 0 (_nullify): "_nullify" "tokens" must be a list of strings',
+    );
+  });
+});
+
+describe('_merge', () => {
+  const defaults = { shipping: { carrier: 'ups', options: { signature: false, insured: true } } };
+
+  it('deep-merges nested fields and keeps the rest of the document', () => {
+    expect(
+      run({ id: 1, shipping: { options: { signature: true } } }, [{ _merge: { value: defaults } }]),
+    ).toEqual({
+      id: 1,
+      shipping: { options: { signature: true, insured: true }, carrier: 'ups' },
+    });
+  });
+
+  it('lets document values win by default and merged values win with overwrite', () => {
+    const doc = { shipping: { carrier: 'fedex' } };
+    expect(run(doc, [{ _merge: { value: defaults } }])).toMatchObject({
+      shipping: { carrier: 'fedex' },
+    });
+    expect(run(doc, [{ _merge: { value: defaults, strategy: 'overwrite' } }])).toMatchObject({
+      shipping: { carrier: 'ups' },
+    });
+  });
+
+  it('replaces arrays whole instead of merging them', () => {
+    const value = { tags: ['x', 'y'] };
+    expect(run({ tags: ['a'] }, [{ _merge: { value } }])).toEqual({ tags: ['a'] });
+    expect(run({ tags: ['a'] }, [{ _merge: { value, strategy: 'overwrite' } }])).toEqual({
+      tags: ['x', 'y'],
+    });
+  });
+
+  it('evaluates references inside the merged value', () => {
+    expect(run({ id: 7 }, [{ _merge: { value: { meta: { source: '$id' } } } }])).toEqual({
+      id: 7,
+      meta: { source: 7 },
+    });
+  });
+
+  it('rejects a non-object value and an unknown strategy', () => {
+    expect(() => run({}, [{ _merge: { value: [1] } }])).toThrow(/must be an object/);
+    expect(() => run({}, [{ _merge: { value: {}, strategy: 'deep' } }])).toThrow(
+      /"keep" or "overwrite"/,
+    );
+  });
+});
+
 describe('_select', () => {
   it('keeps only the named paths (strict projection)', () => {
     expect(
@@ -92,6 +164,72 @@ describe('_select', () => {
   });
 });
 
+describe('_keep', () => {
+  it('keeps only the listed fields, dropping the rest', () => {
+    expect(run({ id: 1, name: 'a', secret: 'x' }, [{ _keep: ['id', 'name'] }])).toEqual({
+      id: 1,
+      name: 'a',
+    });
+  });
+
+  it('keeps a nested path in place, without its siblings', () => {
+    const doc = { id: 1, customer: { name: 'Ada', ssn: '123' }, note: 'n' };
+    expect(run(doc, [{ _keep: ['id', 'customer.name', 'missing'] }])).toEqual({
+      id: 1,
+      customer: { name: 'Ada' },
+    });
+  });
+
+  it('rejects a non-list argument', () => {
+    expect(() => run({ a: 1 }, [{ _keep: { a: true } }])).toThrow(/expects a list of paths/);
+  });
+
+  it('keeps a whole array but refuses a path into one of its elements', () => {
+    const doc = { id: 1, lines: [{ sku: 'a', qty: 2 }] };
+    expect(run(doc, [{ _keep: ['lines'] }])).toEqual({ lines: [{ sku: 'a', qty: 2 }] });
+    expect(() => run(doc, [{ _keep: ['lines[0].sku'] }])).toThrow(
+      /"_keep" path "lines\[0\]\.sku" has an array index/,
+    );
+  });
+});
+
+describe('_parseJson and _stringifyJson', () => {
+  it('parses an embedded JSON string into nested data', () => {
+    const doc = { id: 1, payload: '{"a":1,"tags":["x"]}', empty: null };
+    expect(run(doc, [{ _parseJson: ['payload', 'empty', 'missing'] }])).toEqual({
+      id: 1,
+      payload: { a: 1, tags: ['x'] },
+      empty: null,
+    });
+  });
+
+  it('stringifies a nested object, round-tripping with _parseJson', () => {
+    const doc = { id: 1, meta: { source: 'web', n: [1, 2] } };
+    const out = run(doc, [{ _stringifyJson: ['meta', 'missing'] }]);
+    expect(out).toEqual({ id: 1, meta: '{"source":"web","n":[1,2]}' });
+    expect(run(out, [{ _parseJson: ['meta'] }])).toEqual(doc);
+  });
+
+  it('fails the document on a string that is not JSON, naming the path', () => {
+    expect(() => run({ payload: '{oops' }, [{ _parseJson: ['payload'] }])).toThrow(
+      'step 0 (_parseJson): "_parseJson" target "payload" is not valid JSON: ' +
+        'syntax error at character 2',
+    );
+    expect(() => run({ payload: '{"a":' }, [{ _parseJson: ['payload'] }])).toThrow(
+      /is not valid JSON: unexpected end of input$/,
+    );
+    expect(() => run({ payload: 7 }, [{ _parseJson: ['payload'] }])).toThrow(
+      '"_parseJson" target "payload" is not a string',
+    );
+  });
+
+  it('keeps the text out of the error, since document errors are logged', () => {
+    const parse = () => run({ payload: 'ssn=123-45-6789' }, [{ _parseJson: ['payload'] }]);
+    expect(parse).toThrow('"_parseJson" target "payload" is not valid JSON: syntax error');
+    expect(parse).toThrow(/syntax error$/);
+  });
+});
+
 describe('_when', () => {
   it('runs then/else by an expression condition', () => {
     const steps = (status: string) =>
@@ -115,6 +253,53 @@ describe('_when', () => {
   });
 });
 
+describe('_each', () => {
+  it('runs the steps against each element, resolving $path inside it', () => {
+    expect(
+      run({ items: [{ sku: 'a', qty: 1 }, { sku: 'b', qty: 2 }] }, [
+        { _each: { in: 'items', steps: [{ _rename: { sku: 'code' } }, { _set: { n: '$qty' } }] } },
+      ]),
+    ).toEqual({
+      items: [
+        { qty: 1, code: 'a', n: 1 },
+        { qty: 2, code: 'b', n: 2 },
+      ],
+    });
+  });
+
+  it('leaves an empty array, a missing path, and non-object elements alone', () => {
+    const steps = [{ _each: { in: 'items', steps: [{ _set: { seen: true } }] } }];
+    expect(run({ items: [] }, steps)).toEqual({ items: [] });
+    expect(run({ other: 1 }, steps)).toEqual({ other: 1 });
+    expect(run({ items: [{}, 3, ['x']] }, steps)).toEqual({ items: [{ seen: true }, 3, ['x']] });
+  });
+
+  it('nests over arrays within elements', () => {
+    const steps = [
+      {
+        _each: {
+          in: 'orders',
+          steps: [{ _each: { in: 'lines', steps: [{ _unset: ['internal'] }] } }],
+        },
+      },
+    ];
+    expect(run({ orders: [{ lines: [{ internal: 1, sku: 'a' }] }] }, steps)).toEqual({
+      orders: [{ lines: [{ sku: 'a' }] }],
+    });
+  });
+
+  it('errors on a non-array target and names the failing element', () => {
+    expect(() => run({ items: 1 }, [{ _each: { in: 'items', steps: [] } }])).toThrow(
+      /not an array/,
+    );
+    expect(() =>
+      run({ items: [{}, { tags: 1 }] }, [
+        { _each: { in: 'items', steps: [{ _append: { to: 'tags', value: 'x' } }] } },
+      ]),
+    ).toThrow(/step 0 \(_each\): items\[1\]: step 0 \(_append\)/);
+  });
+});
+
 describe('_ts', () => {
   const functions = {
     addName: (o: { first: string; last: string }) => ({ ...o, name: `${o.first} ${o.last}` }),
@@ -162,4 +347,46 @@ describe('applyFlow', () => {
   it('throws on an unknown operator with step context', () => {
     expect(() => run({}, [{ _frobnicate: 1 }])).toThrow(/step 0: unknown operator "_frobnicate"/);
   });
+
+  it('records the steps a failure happened in, outermost first, and the bare reason', () => {
+    const inner = { _set: { placedAt: { _toIso: '$placed' } } };
+    const outer = { _each: { in: 'orders', steps: [inner] } };
+    let caught: unknown;
+    try {
+      run({ orders: [{ placed: 'soon' }] }, [{ _set: { a: 1 } }, outer]);
+    } catch (err) {
+      caught = err;
+    }
+    expect(caught).toBeInstanceOf(TransformError);
+    const { frames, reason, message } = caught as TransformError;
+    expect(message).toBe(
+      'step 1 (_each): orders[0]: step 0 (_set): "_toIso" got an unparseable date',
+    );
+    expect(reason).toBe('"_toIso" got an unparseable date');
+    expect(frames).toEqual([
+      { index: 1, op: '_each', step: outer },
+      { index: 0, op: '_set', step: inner },
+    ]);
+  });
+});
+
+describe('onEmptyOutput', () => {
+  const wipe: Flow['steps'] = [{ _unset: ['a'] }];
+  const outOf = (flow: Flow) => applyFlow(docOf({ a: 1 }), flow);
+
+  it('skips an empty output by default and writes a non-empty one', () => {
+    expect(skipsOutput({ steps: wipe }, outOf({ steps: wipe }))).toBe(true);
+    expect(skipsOutput({ steps: [] }, outOf({ steps: [] }))).toBe(false);
+  });
+
+  it('writes an empty output under allow', () => {
+    const flow: Flow = { steps: wipe, onEmptyOutput: 'allow' };
+    expect(toValue(outOf(flow).root)).toEqual({});
+    expect(skipsOutput(flow, outOf(flow))).toBe(false);
+  });
+
+  it('fails the document under error', () => {
+    expect(() => outOf({ steps: wipe, onEmptyOutput: 'error' })).toThrow(/empty document/);
+    expect(() => outOf({ steps: [], onEmptyOutput: 'error' })).not.toThrow();
+  });
 });
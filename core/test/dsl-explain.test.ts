@@ -0,0 +1,145 @@
+import { describe, expect, it } from 'vitest';
+import type { Flow } from '../src/dsl/engine.js';
+import { describeExpr, diagramFlow, explainFlow } from '../src/dsl/explain.js';
+
+// Mirrors examples/golden-path/flows/order.yaml.
+const order: Flow = {
+  steps: [
+    {
+      _set: {
+        id: { _upper: '$id' },
+        name: { _concat: { parts: ['$first', '$last'], sep: ' ' } },
+      },
+    },
+    {
+      _when: {
+        cond: { _eq: ['$status', 'new'] },
+        then: [{ _set: { priority: 'high' } }],
+        else: [{ _set: { priority: 'normal' } }],
+      },
+    },
+    { _ts: { module: 'initials' } },
+  ],
+};
+
+describe('describeExpr', () => {
+  it('renders references, literals, and operators compactly', () => {
+    expect(describeExpr('$order.id')).toBe('$order.id');
+    expect(describeExpr('new')).toBe('"new"');
+    expect(describeExpr('$$5')).toBe('"$5"');
+    expect(describeExpr(3)).toBe('3');
+    expect(describeExpr({ _lit: { a: 1 } })).toBe('{"a":1}');
+    expect(describeExpr({ _upper: '$id' })).toBe('_upper($id)');
+    expect(describeExpr({ _concat: { parts: ['$a', '$b'], sep: '-' } })).toBe(
+      '_concat(parts: [$a, $b], sep: "-")',
+    );
+    expect(describeExpr({ x: '$a', y: [1] })).toBe('{ x: $a, y: [1] }');
+  });
+});
+
+describe('explainFlow', () => {
+  it('narrates the golden-path order flow', () => {
+    expect(explainFlow(order)).toBe(
+      [
+        '1. Set id to _upper($id) and name to _concat(parts: [$first, $last], sep: " ")',
+        '2. When _eq($status, "new"):',
+        '   1. Set priority to "high"',
+        '   Otherwise:',
+        '   1. Set priority to "normal"',
+        '3. Run the custom function initials on the whole document, replacing the document',
+      ].join('\n'),
+    );
+  });
+
+  it('wraps paths and expressions in code spans for markdown', () => {
+    expect(explainFlow({ steps: [{ _rename: { cust_id: 'customer_id' } }] }, 'markdown')).toBe(
+      '1. Rename `cust_id` to `customer_id`',
+    );
+  });
+
+  it('nests the steps of _each under the array they run over', () => {
+    const flow: Flow = {
+      steps: [{ _each: { in: 'items', steps: [{ _unset: ['internal'] }] } }],
+    };
+    expect(explainFlow(flow).split('\n')).toEqual([
+      '1. For each element of items:',
+      '   1. Remove 1 field: internal',
+    ]);
+  });
+
+  it('counts and lists removed fields', () => {
+    expect(explainFlow({ steps: [{ _unset: ['a', 'b.c', 'd'] }] })).toBe(
+      '1. Remove 3 fields: a, b.c, d',
+    );
+  });
+
+  it('describes defaults, appends, merges, reshapes, and targeted _ts steps', () => {
+    const flow: Flow = {
+      steps: [
+        { _default: { currency: 'USD' } },
+        { _append: { to: 'tags', value: '$kind' } },
+        { _merge: { value: { meta: { v: 1 } } } },
+        { _select: { id: '$order.id' } },
+        { _ts: { module: 'score', from: 'order', to: 'score' } },
+        { _nullify: { paths: ['note'], tokens: ['N/A'] } },
+        { _keep: ['id', 'score'] },
+        { _parseJson: ['payload'] },
+        { _stringifyJson: ['meta', 'tags'] },
+      ],
+    };
+    expect(explainFlow(flow).split('\n')).toEqual([
+      '1. Default currency to "USD" where absent',
+      '2. Append $kind to the array at tags',
+      '3. Deep-merge { meta: { v: 1 } } into the document (on conflict the document wins)',
+      '4. Reshape the document to only id = $order.id',
+      '5. Run the custom function score on order, writing score',
+      '6. Replace the strings "N/A" with null under note',
+      '7. Keep only id, score, dropping every other field',
+      '8. Parse the JSON text in payload into nested data',
+      '9. Replace meta and tags with their JSON text',
+    ]);
+  });
+});
+
+describe('diagramFlow', () => {
+  it('draws the golden-path order flow from input to output, steps in order', () => {
+    const ends = {
+      inputs: ['file in/order.json (pipeline order)'],
+      outputs: ['file out/order.json (pipeline order)'],
+    };
+    expect(diagramFlow(order, ends).split('\n')).toEqual([
+      'file in/order.json (pipeline order)',
+      '  |',
+      '  v',
+      '1. _set id, name  [on error: fail the document]',
+      '  |',
+      '  v',
+      '2. _when _eq($status, "new") (then 1 step, else 1 step)  [on error: fail the document]',
+      '  |',
+      '  v',
+      '3. _ts initials (document -> document)  [on error: fail the document]',
+      '  |',
+      '  v',
+      'file out/order.json (pipeline order)  [on empty output: skip]',
+    ]);
+  });
+
+  it('names bare ends when no pipeline is given, and the flow\'s empty-output policy', () => {
+    const flow: Flow = {
+      steps: [{ _rename: { a: 'b' } }, { _each: { in: 'items', steps: [] } }],
+      onEmptyOutput: 'allow',
+    };
+    expect(diagramFlow(flow).split('\n')).toEqual([
+      'input',
+      '  |',
+      '  v',
+      '1. _rename a -> b  [on error: fail the document]',
+      '  |',
+      '  v',
+      '2. _each items (0 steps)  [on error: fail the document]',
+      '  |',
+      '  v',
+      'output  [on empty output: allow]',
+    ]);
+  });
+});
@@ -100,3 +100,36 @@ describe('value operators', () => {
     );
   });
 });
+
+describe('_uuid5', () => {
+  const ctx = ctxOf({ id: 'A-1', other: 'A-2', n: 7, word: 'héllo ✓' });
+
+  it('matches the RFC 9562 name-based algorithm', () => {
+    expect(evalExpr({ _uuid5: ['dns', 'www.example.com'] }, ctx)).toBe(
+      '2ed6657d-e927-568b-95e1-2665a8aea6a2',
+    );
+    expect(evalExpr({ _uuid5: ['6ba7b810-9dad-11d1-80b4-00c04fd430c8', '$id'] }, ctx)).toBe(
+      '928887a2-8361-5602-a4fd-70c604065418',
+    );
+    expect(evalExpr({ _uuid5: ['dns', '$word'] }, ctx)).toBe(
+      '4a45ac82-d56e-53f4-89dd-028be093a327',
+    );
+  });
+
+  it('gives identical inputs identical ids and different inputs different ones', () => {
+    const id = evalExpr({ _uuid5: ['url', '$id'] }, ctx);
+    expect(evalExpr({ _uuid5: ['url', '$id'] }, ctxOf({ id: 'A-1' }))).toBe(id);
+    expect(evalExpr({ _uuid5: ['url', '$other'] }, ctx)).not.toBe(id);
+    expect(evalExpr({ _uuid5: ['dns', '$id'] }, ctx)).not.toBe(id);
+    expect(evalExpr({ _uuid5: ['url', '$n'] }, ctx)).toBe(evalExpr({ _uuid5: ['url', '7'] }, ctx));
+  });
+
+  it('rejects an unknown namespace and a missing name', () => {
+    expect(() => evalExpr({ _uuid5: ['orders', '$id'] }, ctx)).toThrow(
+      '"_uuid5" namespace must be a UUID or one of dns, url, oid, x500',
+    );
+    expect(() => evalExpr({ _uuid5: ['dns', '$missing'] }, ctx)).toThrow(
+      '"_uuid5" has no name to hash',
+    );
+  });
+});
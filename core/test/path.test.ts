@@ -16,14 +16,64 @@ describe('parsePath', () => {
   it('keeps a numeric object key as a string', () => {
     expect(parsePath('counts.0')).toEqual(['counts', '0']);
   });
+
+  it('parses quoted keys that contain separators, with JSON escapes', () => {
+    expect(parsePath('headers["content.type"]')).toEqual(['headers', 'content.type']);
+    expect(parsePath('["a.b"].c')).toEqual(['a.b', 'c']);
+    expect(parsePath('m["[0]"][1]')).toEqual(['m', '[0]', 1]);
+    expect(parsePath('[""]')).toEqual(['']);
+    expect(parsePath('["say \\"hi\\""]')).toEqual(['say "hi"']);
+  });
+
+  it('keeps XML attribute and text keys bare', () => {
+    expect(parsePath('order.@id')).toEqual(['order', '@id']);
+    expect(parsePath('note.#text')).toEqual(['note', '#text']);
+  });
+
+  it('rejects a malformed path with its position', () => {
+    const cases: [string, RegExp][] = [
+      ['a..b', /empty segment at character 3/],
+      ['.a', /empty segment at character 1/],
+      ['a.', /empty segment at character 3/],
+      ['a.[0]', /empty segment at character 3/],
+      ['a[', /expected \[<index>\] or \["<key>"\] at character 2/],
+      ['a[x]', /expected \[<index>\]/],
+      ['a["b]', /expected \[<index>\]/],
+      ['["\\q"]', /quoted key is not a valid JSON string at character 1/],
+      ['a["tab\there"]', /quoted key is not a valid JSON string at character 2/],
+      ['a]', /unexpected "\]" at character 2/],
+      ['a[0]b', /unexpected "b" at character 5/],
+    ];
+    for (const [path, message] of cases) {
+      expect(() => parsePath(path), path).toThrow(PathError);
+      expect(() => parsePath(path), path).toThrow(message);
+    }
+  });
 });
 
 describe('formatPath', () => {
   it('is the inverse of parsePath', () => {
-    for (const path of ['lines[0].sku', 'a.b.c', 'matrix[1][2]', 'counts.0']) {
+    for (const path of [
+      'lines[0].sku',
+      'a.b.c',
+      'matrix[1][2]',
+      'counts.0',
+      'headers["content.type"]',
+      '["a.b"].c',
+      '[""]',
+    ]) {
       expect(formatPath(parsePath(path))).toBe(path);
     }
   });
+
+  it('quotes exactly the keys that cannot be written bare', () => {
+    const keys = ['plain', '@id', 'a.b', 'a[0]', 'x]', '', 'quo"te', 'back\\slash', 'new\nline'];
+    for (const key of keys) {
+      const path = formatPath(['root', key]);
+      expect(parsePath(path), path).toEqual(['root', key]);
+      expect(path.includes('["'), path).toBe(!/^[^.[\]]+$/.test(key));
+    }
+  });
 });
 
 describe('get', () => {
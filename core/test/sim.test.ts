@@ -0,0 +1,96 @@
+import { describe, expect, it } from 'vitest';
+import { type SimSpec, SimSpecError, createSimulator, seededRandom } from '../src/sim.js';
+
+const take = (spec: SimSpec, n: number, seed?: number) => {
+  const next = createSimulator(spec, { seed, now: Date.parse('2026-01-01T00:00:00Z') });
+  return Array.from({ length: n }, () => next());
+};
+
+describe('seededRandom', () => {
+  it('repeats its sequence for a seed and stays in [0, 1)', () => {
+    const a = seededRandom(7);
+    const b = seededRandom(7);
+    const draws = Array.from({ length: 1000 }, () => a());
+    expect(Array.from({ length: 1000 }, () => b())).toEqual(draws);
+    expect(draws.every((x) => x >= 0 && x < 1)).toBe(true);
+    expect(seededRandom(8)()).not.toBe(draws[0]);
+  });
+});
+
+describe('createSimulator', () => {
+  const spec: SimSpec = {
+    fields: {
+      id: { type: 'uuid' },
+      customer: { type: 'object', fields: { name: { type: 'name' } } },
+      status: { type: 'enum', values: ['new', 'shipped'] },
+      total: { type: 'int', min: 1, max: 500 },
+    },
+  };
+
+  it('yields the same documents for the same seed, and others for another', () => {
+    expect(take(spec, 20, 42)).toEqual(take(spec, 20, 42));
+    expect(take(spec, 20, 42)).not.toEqual(take(spec, 20, 43));
+  });
+
+  it('uses the spec seed unless the caller passes one', () => {
+    expect(take({ ...spec, seed: 5 }, 5)).toEqual(take(spec, 5, 5));
+    expect(take({ ...spec, seed: 5 }, 5, 6)).toEqual(take(spec, 5, 6));
+  });
+
+  it('generates every field in spec order, nesting objects', () => {
+    const [doc] = take(spec, 1) as Record<string, unknown>[];
+    expect(Object.keys(doc)).toEqual(['id', 'customer', 'status', 'total']);
+    expect(doc.id).toMatch(/^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/);
+    expect((doc.customer as { name: string }).name).toMatch(/^[A-Z][a-z]+ [A-Z][a-z]+$/);
+  });
+
+  it('keeps ints inside their inclusive range and reaches both ends', () => {
+    const totals = take({ fields: { n: { type: 'int', min: 1, max: 3 } } }, 300).map(
+      (doc) => (doc as { n: number }).n,
+    );
+    expect(new Set(totals)).toEqual(new Set([1, 2, 3]));
+  });
+
+  it('draws enum values in proportion to their weights, never a zero weight', () => {
+    const field = { type: 'enum', values: ['a', 'b', 'c'], weights: [3, 1, 0] } as const;
+    const draws = take({ fields: { v: { ...field, values: [...field.values] } } }, 4000).map(
+      (doc) => (doc as { v: string }).v,
+    );
+    const a = draws.filter((v) => v === 'a').length;
+    expect(draws).not.toContain('c');
+    expect(a / draws.length).toBeGreaterThan(0.7);
+    expect(a / draws.length).toBeLessThan(0.8);
+  });
+
+  it('steps timestamps from their start, jittered within bounds', () => {
+    const stamps = take(
+      {
+        fields: {
+          at: { type: 'timestamp', start: '2026-03-01T00:00:00Z', stepMs: 1000, jitterMs: 200 },
+        },
+      },
+      50,
+    ).map((doc) => Date.parse((doc as { at: string }).at));
+    const start = Date.parse('2026-03-01T00:00:00Z');
+    stamps.forEach((at, i) => expect(Math.abs(at - (start + i * 1000))).toBeLessThanOrEqual(200));
+    expect(new Set(stamps.map((at, i) => at - i * 1000)).size).toBeGreaterThan(1);
+  });
+
+  it('starts a timestamp without a start at the simulation clock', () => {
+    const [doc] = take({ fields: { at: { type: 'timestamp' } } }, 1);
+    expect(doc).toEqual({ at: '2026-01-01T00:00:00.000Z' });
+  });
+
+  it('refuses a spec that cannot generate, naming the field', () => {
+    const bad = (fields: SimSpec['fields']) => () => createSimulator({ fields });
+    expect(bad({ o: { type: 'object', fields: { n: { type: 'int', min: 5, max: 1 } } } })).toThrow(
+      new SimSpecError('o.n: int needs integers with min <= max, got 5..1'),
+    );
+    expect(bad({ s: { type: 'enum', values: [] } })).toThrow('s: enum has no values');
+    expect(bad({ s: { type: 'enum', values: ['a'], weights: [1, 2] } })).toThrow(
+      's: enum has 1 values but 2 weights',
+    );
+    expect(bad({ s: { type: 'enum', values: ['a'], weights: [0] } })).toThrow(/positive sum/);
+    expect(bad({ t: { type: 'timestamp', start: 'soon' } })).toThrow(/not an ISO date/);
+  });
+});
@@ -11,8 +11,10 @@
 //! artifact. The `manifest.json` inside the artifact is the authoritative
 //! contract the engine reads (see `manifest.rs`).
 
-use anyhow::{Result, bail};
+use crate::runner::Options;
+use anyhow::{Context, Result, bail};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Default mounted config path (k8s ConfigMap / volume convention).
 pub const DEFAULT_CONFIG: &str = "/etc/weavster/weavster.yaml";
@@ -21,19 +23,45 @@ pub const DEFAULT_CONFIG: &str = "/etc/weavster/weavster.yaml";
 pub const PROJECT_FILE: &str = "weavster.yaml";
 
 pub const USAGE: &str = "\
-usage: weavster-engine [-c|--config <weavster.yaml>] [--artifact <dir>]
+usage: weavster-engine [-c|--config <weavster.yaml>] [--artifact <dir>] [options]
 
-  -c, --config <path>   project config to boot from
-                        (default: /etc/weavster/weavster.yaml)
-      --artifact <dir>  compiled artifact directory
-                        (default: <config-dir>/target/artifact)
-  -h, --help            show this help";
+  -c, --config <path>           project config to boot from
+                                (default: /etc/weavster/weavster.yaml)
+      --artifact <dir>          compiled artifact directory
+                                (default: <config-dir>/target/artifact)
+      --max-message-bytes <n>   refuse any document larger than <n> bytes
+      --once-per-pipeline       run one document through each pipeline, then exit
+      --pull-timeout-ms <n>     warn when a source takes over <n> ms to yield a
+                                document; fail the pipeline after 3 in a row
+      --max-runtime <duration>  stop pulling after <duration> (e.g. 30s, 5m,
+                                500ms), finish in-flight documents, exit 0
+  -q, --quiet                   log warnings and errors only
+      --output <text|json>      end-of-run summary format; json prints one
+                                document to stdout (default: text)
+  -h, --help                    show this help
 
-/// A resolved boot plan: the config to boot from and the artifact to run.
+Exits 0 when every pipeline ran, 1 when any failed or the run could not start.";
+
+/// A resolved boot plan: the config to boot from, the artifact to run, the
+/// run-loop options, and how the run reports itself.
 #[derive(Debug)]
 pub struct Boot {
     pub config: PathBuf,
     pub artifact: PathBuf,
+    pub options: Options,
+    /// Drop info-level log lines, keeping warnings and errors.
+    pub quiet: bool,
+    pub output: Output,
+}
+
+/// How the end-of-run summary is printed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    /// Human status lines on stderr.
+    #[default]
+    Text,
+    /// The status lines, plus the summary as one JSON document on stdout.
+    Json,
 }
 
 /// What the parsed arguments asked for.
@@ -50,6 +78,9 @@ pub enum Cli {
 pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Cli> {
     let mut config: Option<PathBuf> = None;
     let mut artifact: Option<PathBuf> = None;
+    let mut options = Options::default();
+    let mut quiet = false;
+    let mut output = Output::default();
 
     let mut args = args.into_iter();
     while let Some(arg) = args.next() {
@@ -57,12 +88,30 @@ pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Cli> {
             "-h" | "--help" => return Ok(Cli::Help),
             "-c" | "--config" => config = Some(take_path(&mut args, &arg)?),
             "--artifact" => artifact = Some(take_path(&mut args, &arg)?),
+            "--max-message-bytes" => {
+                options.max_message_bytes = Some(take_count(&mut args, &arg)?);
+            }
+            "--once-per-pipeline" => options.once_per_pipeline = true,
+            "--pull-timeout-ms" => {
+                let ms = take_count(&mut args, &arg)?;
+                options.pull_timeout = Some(Duration::from_millis(ms as u64));
+            }
+            "--max-runtime" => options.max_runtime = Some(take_duration(&mut args, &arg)?),
+            "-q" | "--quiet" => quiet = true,
+            "--output" => output = take_output(&mut args, &arg)?,
             other => bail!("unknown argument \"{other}\"\n\n{USAGE}"),
         }
     }
 
     let config = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
-    Ok(Cli::Run(resolve(config, artifact)))
+    let (config, artifact) = resolve(config, artifact);
+    Ok(Cli::Run(Boot {
+        config,
+        artifact,
+        options,
+        quiet,
+        output,
+    }))
 }
 
 /// Take the next argument as a flag's path value. A missing value — whether the
@@ -75,9 +124,69 @@ fn take_path<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<Pat
     }
 }
 
+/// Take the next argument as a flag's positive integer value.
+fn take_count<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<usize> {
+    let value = match args.next() {
+        Some(value) if !is_flag(&value) => value,
+        _ => bail!("{flag} needs a number"),
+    };
+    match value.parse::<usize>() {
+        Ok(0) => bail!("{flag} must be greater than zero"),
+        parsed => parsed.with_context(|| format!("{flag} needs a number, got \"{value}\"")),
+    }
+}
+
+/// Take the next argument as a flag's duration: a positive whole number with a
+/// unit, `ms`, `s`, `m`, or `h` (`30s`, `500ms`, `5m`).
+fn take_duration<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<Duration> {
+    let value = match args.next() {
+        Some(value) if !is_flag(&value) => value,
+        _ => bail!("{flag} needs a duration"),
+    };
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let unit_ms = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => 0,
+    };
+    match amount.parse::<u64>() {
+        Ok(0) if unit_ms > 0 => bail!("{flag} must be greater than zero"),
+        Ok(n) if unit_ms > 0 => Ok(Duration::from_millis(n.saturating_mul(unit_ms))),
+        _ => bail!("{flag} needs a duration like 30s, 5m, or 500ms, got \"{value}\""),
+    }
+}
+
+/// Take the next argument as the summary format.
+fn take_output<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<Output> {
+    match args.next().as_deref() {
+        Some("text") => Ok(Output::Text),
+        Some("json") => Ok(Output::Json),
+        Some(value) if !is_flag(value) => bail!("{flag} must be text or json, got \"{value}\""),
+        _ => bail!("{flag} needs a format (text or json)"),
+    }
+}
+
 /// Whether a token is one of our option flags (so it can't be a flag's value).
 fn is_flag(token: &str) -> bool {
-    matches!(token, "-h" | "--help" | "-c" | "--config" | "--artifact")
+    matches!(
+        token,
+        "-h" | "--help"
+            | "-c"
+            | "--config"
+            | "--artifact"
+            | "--max-message-bytes"
+            | "--once-per-pipeline"
+            | "--pull-timeout-ms"
+            | "--max-runtime"
+            | "-q"
+            | "--quiet"
+            | "--output"
+    )
 }
 
 /// Resolve the `-c` path to a project file and an artifact directory. If it
@@ -87,7 +196,7 @@ fn is_flag(token: &str) -> bool {
 /// path — it is taken as the config file itself. The artifact defaults to
 /// `<project-dir>/target/artifact` — `weavster compile`'s default output —
 /// unless `--artifact` overrode it.
-fn resolve(config: PathBuf, artifact: Option<PathBuf>) -> Boot {
+fn resolve(config: PathBuf, artifact: Option<PathBuf>) -> (PathBuf, PathBuf) {
     let (config, project_dir) = if config.is_dir() {
         let file = config.join(PROJECT_FILE);
         (file, config)
@@ -100,7 +209,7 @@ fn resolve(config: PathBuf, artifact: Option<PathBuf>) -> Boot {
         (config, dir)
     };
     let artifact = artifact.unwrap_or_else(|| project_dir.join("target/artifact"));
-    Boot { config, artifact }
+    (config, artifact)
 }
 
 #[cfg(test)]
@@ -176,6 +285,91 @@ mod tests {
         assert_eq!(boot.artifact, Path::new("/data/art"));
     }
 
+    #[test]
+    fn max_message_bytes_defaults_to_off_and_takes_a_count() {
+        assert_eq!(parse_run(&[]).options.max_message_bytes, None);
+        let boot = parse_run(&["--max-message-bytes", "1048576"]);
+        assert_eq!(boot.options.max_message_bytes, Some(1_048_576));
+    }
+
+    #[test]
+    fn max_message_bytes_rejects_a_non_number_and_zero() {
+        let err = parse(["--max-message-bytes".to_string(), "big".to_string()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("needs a number, got \"big\""), "{err}");
+        let err = parse(["--max-message-bytes".to_string(), "0".to_string()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("greater than zero"), "{err}");
+    }
+
+    #[test]
+    fn once_per_pipeline_is_a_switch() {
+        assert!(!parse_run(&[]).options.once_per_pipeline);
+        assert!(
+            parse_run(&["--once-per-pipeline"])
+                .options
+                .once_per_pipeline
+        );
+    }
+
+    #[test]
+    fn pull_timeout_takes_milliseconds() {
+        assert_eq!(parse_run(&[]).options.pull_timeout, None);
+        let boot = parse_run(&["--pull-timeout-ms", "1500"]);
+        assert_eq!(boot.options.pull_timeout, Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn max_runtime_takes_a_duration_with_a_unit() {
+        assert_eq!(parse_run(&[]).options.max_runtime, None);
+        for (arg, expected) in [
+            ("500ms", Duration::from_millis(500)),
+            ("30s", Duration::from_secs(30)),
+            ("5m", Duration::from_secs(300)),
+            ("1h", Duration::from_secs(3600)),
+        ] {
+            let boot = parse_run(&["--max-runtime", arg]);
+            assert_eq!(boot.options.max_runtime, Some(expected), "{arg}");
+        }
+    }
+
+    #[test]
+    fn max_runtime_rejects_a_missing_unit_and_zero() {
+        for bad in ["30", "s", "1.5s", "10d"] {
+            let err = parse(["--max-runtime".to_string(), bad.to_string()])
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("needs a duration like 30s"), "{bad}: {err}");
+        }
+        let err = parse(["--max-runtime".to_string(), "0s".to_string()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("greater than zero"), "{err}");
+    }
+
+    #[test]
+    fn quiet_and_output_default_to_off_and_text() {
+        let boot = parse_run(&[]);
+        assert!(!boot.quiet);
+        assert_eq!(boot.output, Output::Text);
+        let boot = parse_run(&["-q", "--output", "json"]);
+        assert!(boot.quiet);
+        assert_eq!(boot.output, Output::Json);
+        assert!(parse_run(&["--quiet"]).quiet);
+    }
+
+    #[test]
+    fn output_rejects_an_unknown_format() {
+        let err = parse(["--output".to_string(), "yaml".to_string()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("must be text or json, got \"yaml\""), "{err}");
+        let err = parse(["--output".to_string()]).unwrap_err().to_string();
+        assert!(err.contains("needs a format"), "{err}");
+    }
+
     #[test]
     fn help_flag_short_and_long() {
         assert!(matches!(parse(["-h".to_string()]).unwrap(), Cli::Help));
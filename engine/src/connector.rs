@@ -1,16 +1,17 @@
 //! Connector traits (Engine Plan E4): the seam between the engine's run loop
 //! and the outside world. A `Source` yields documents in order; a `Sink`
-//! writes them. `file` is the only connector this phase; later connectors
+//! writes them. `file` and `sqlite` are the connectors this phase; later ones
 //! (rest/blob/tcp/grpc/db) implement the same traits and register in
 //! `registry`, so they are additive — no run-loop change.
 //!
-//! The traits are async because every connector beyond `file` is async I/O;
+//! The traits are async because most connectors beyond `file` are async I/O;
 //! landing the async shape now means those connectors slot in without a
 //! breaking trait change. The transform itself stays synchronous (it runs in
 //! `spawn_blocking` off the async worker).
 
 use anyhow::Result;
 use async_trait::async_trait;
+use std::fmt;
 
 /// One document a source yields: its text payload plus an origin label used in
 /// logs and error messages (e.g. the file path it came from, or a URL). A
@@ -33,3 +34,74 @@ pub trait Sink: Send {
     /// Write one serialized document.
     async fn write(&mut self, payload: &str) -> Result<()>;
 }
+
+/// A connector failure naming the connector type and the operation that
+/// failed (`open`/`next`/`write`), so a log line pinpoints it. The registry
+/// wraps every connector's errors in this; connectors themselves return plain
+/// `anyhow` errors with their own context (paths, URLs).
+#[derive(Debug)]
+pub struct ConnectorError {
+    pub connector: String,
+    pub operation: &'static str,
+    pub message: String,
+}
+
+impl ConnectorError {
+    pub fn wrap(connector: &str, operation: &'static str, err: anyhow::Error) -> anyhow::Error {
+        anyhow::Error::new(Self {
+            connector: connector.to_string(),
+            operation,
+            message: format!("{err:#}"),
+        })
+    }
+}
+
+impl fmt::Display for ConnectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} connector {} failed: {}",
+            self.connector, self.operation, self.message
+        )
+    }
+}
+
+impl std::error::Error for ConnectorError {}
+
+/// A document over the run's `--max-message-bytes` limit, refused by its
+/// source from the document's size alone, before the payload is read into
+/// memory. It is the document's failure rather than the connector's, so the
+/// registry passes it through unwrapped and the run loop reports it per
+/// document.
+#[derive(Debug)]
+pub struct MessageTooLarge {
+    pub origin: String,
+    pub size: u64,
+    pub max: usize,
+}
+
+impl MessageTooLarge {
+    /// Fail when `size` is over `max`; no limit passes everything.
+    pub fn check(max: Option<usize>, size: u64, origin: impl FnOnce() -> String) -> Result<()> {
+        match max {
+            Some(max) if size > max as u64 => Err(anyhow::Error::new(Self {
+                origin: origin(),
+                size,
+                max,
+            })),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for MessageTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is {} bytes, over the {}-byte --max-message-bytes limit",
+            self.origin, self.size, self.max
+        )
+    }
+}
+
+impl std::error::Error for MessageTooLarge {}
@@ -1,19 +1,23 @@
 //! The `file` connector (Engine Plan E4): a glob source and a path sink, both
 //! resolved against the connector root (the artifact directory).
 
-use crate::connector::{Sink, Source, SourceDoc};
+use crate::connector::{MessageTooLarge, Sink, Source, SourceDoc};
 use anyhow::{Context, Result, bail};
 use async_trait::async_trait;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 /// Reads each file a glob matches, in sorted (input) order. One file is one
-/// document this phase; multi-record files are a later expansion.
+/// document this phase; multi-record files are a later expansion. A file over
+/// `max_bytes` is refused from its metadata, without reading it.
 ///
 /// `pub(crate)`: the only caller is `registry::build_source`, reached after
 /// manifest validation, so `new` can trust the glob is root-relative.
 pub(crate) struct FileSource {
     remaining: VecDeque<PathBuf>,
+    max_bytes: Option<usize>,
 }
 
 impl FileSource {
@@ -21,7 +25,7 @@ impl FileSource {
     /// fails at startup rather than mid-run. The manifest gate
     /// (`manifest::check_contained`) guarantees `glob` is relative and free of
     /// `..`, so `root.join` stays inside the connector root.
-    pub(crate) fn new(root: &Path, glob: &str) -> Result<Self> {
+    pub(crate) fn new(root: &Path, glob: &str, max_bytes: Option<usize>) -> Result<Self> {
         let joined = root.join(glob);
         let pattern = joined.to_str().context("glob pattern is not valid UTF-8")?;
         let mut paths: Vec<PathBuf> = glob::glob(pattern)
@@ -34,6 +38,7 @@ impl FileSource {
         }
         Ok(Self {
             remaining: paths.into(),
+            max_bytes,
         })
     }
 }
@@ -44,9 +49,19 @@ impl Source for FileSource {
         let Some(path) = self.remaining.pop_front() else {
             return Ok(None);
         };
+        let origin = || path.display().to_string();
+        if self.max_bytes.is_some() {
+            let size = tokio::fs::metadata(&path)
+                .await
+                .with_context(|| format!("cannot read {}", path.display()))?
+                .len();
+            MessageTooLarge::check(self.max_bytes, size, origin)?;
+        }
         let payload = tokio::fs::read_to_string(&path)
             .await
             .with_context(|| format!("cannot read {}", path.display()))?;
+        // A file that grew after the metadata check is still held to the limit.
+        MessageTooLarge::check(self.max_bytes, payload.len() as u64, origin)?;
         Ok(Some(SourceDoc {
             origin: path.display().to_string(),
             payload,
@@ -54,11 +69,29 @@ impl Source for FileSource {
     }
 }
 
+/// One write lock per destination path, shared by every sink in a run that
+/// writes there. Pipelines run concurrently, and two overwrites of the same
+/// file racing each other can leave it holding a mix of both documents;
+/// taking the path's lock around each write keeps every write whole.
+#[derive(Default)]
+pub(crate) struct WriteLocks {
+    locks: HashMap<PathBuf, Arc<Mutex<()>>>,
+}
+
+impl WriteLocks {
+    fn claim(&mut self, path: &Path) -> Arc<Mutex<()>> {
+        Arc::clone(self.locks.entry(path.to_path_buf()).or_default())
+    }
+}
+
 /// Writes to a single path, overwriting per document (last write wins) — the
 /// TS file connector's semantics. Per-document naming for multi-match globs is
-/// a later decision. `pub(crate)`: built only by `registry::build_sink`.
+/// a later decision. Sinks sharing a path share its [`WriteLocks`] entry, so
+/// pipelines writing one file take turns. `pub(crate)`: built only by
+/// `registry::build_sink`.
 pub(crate) struct FileSink {
     path: PathBuf,
+    lock: Arc<Mutex<()>>,
 }
 
 impl FileSink {
@@ -67,19 +100,21 @@ impl FileSink {
     /// `path` inside the connector root. The `std::fs` call is blocking, but
     /// it's a one-shot at startup before any task runs — off the hot path, so
     /// not worth a `spawn_blocking` hop.
-    pub(crate) fn new(root: &Path, path: &str) -> Result<Self> {
+    pub(crate) fn new(root: &Path, path: &str, locks: &mut WriteLocks) -> Result<Self> {
         let path = root.join(path);
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)
                 .with_context(|| format!("cannot create {}", parent.display()))?;
         }
-        Ok(Self { path })
+        let lock = locks.claim(&path);
+        Ok(Self { path, lock })
     }
 }
 
 #[async_trait]
 impl Sink for FileSink {
     async fn write(&mut self, payload: &str) -> Result<()> {
+        let _turn = self.lock.lock().await;
         tokio::fs::write(&self.path, payload)
             .await
             .with_context(|| format!("cannot write {}", self.path.display()))
@@ -89,6 +124,8 @@ impl Sink for FileSink {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
 
     fn temp(name: &str) -> PathBuf {
         let dir = std::env::temp_dir().join(format!("wv-file-{name}-{}", std::process::id()));
@@ -114,7 +151,7 @@ mod tests {
         std::fs::write(dir.join("in/a.json"), "A").unwrap();
 
         block_on(async {
-            let mut source = FileSource::new(&dir, "in/*.json").unwrap();
+            let mut source = FileSource::new(&dir, "in/*.json", None).unwrap();
             let first = source.next().await.unwrap().unwrap();
             let second = source.next().await.unwrap().unwrap();
             assert_eq!(first.payload, "A");
@@ -125,10 +162,31 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn source_refuses_a_file_over_the_limit_and_passes_one_at_it() {
+        let dir = temp("limit");
+        std::fs::create_dir_all(dir.join("in")).unwrap();
+        std::fs::write(dir.join("in/a.json"), "12345678").unwrap();
+        std::fs::write(dir.join("in/b.json"), "123456789").unwrap();
+
+        block_on(async {
+            let mut source = FileSource::new(&dir, "in/*.json", Some(8)).unwrap();
+            assert_eq!(source.next().await.unwrap().unwrap().payload, "12345678");
+            let err = source.next().await.err().unwrap();
+            let refused = err
+                .downcast_ref::<MessageTooLarge>()
+                .expect("a MessageTooLarge");
+            assert!(refused.origin.ends_with("b.json"), "{err}");
+            assert_eq!((refused.size, refused.max), (9, 8));
+        });
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn source_rejects_an_empty_match() {
         let dir = temp("empty");
-        let err = FileSource::new(&dir, "in/*.json")
+        let err = FileSource::new(&dir, "in/*.json", None)
             .err()
             .unwrap()
             .to_string();
@@ -140,7 +198,7 @@ mod tests {
     fn sink_writes_the_payload_creating_parents() {
         let dir = temp("sink");
         block_on(async {
-            let mut sink = FileSink::new(&dir, "out/x.json").unwrap();
+            let mut sink = FileSink::new(&dir, "out/x.json", &mut WriteLocks::default()).unwrap();
             sink.write("hello").await.unwrap();
         });
         assert_eq!(
@@ -154,7 +212,7 @@ mod tests {
     fn sink_overwrites_per_write_last_one_wins() {
         let dir = temp("overwrite");
         block_on(async {
-            let mut sink = FileSink::new(&dir, "out/x.json").unwrap();
+            let mut sink = FileSink::new(&dir, "out/x.json", &mut WriteLocks::default()).unwrap();
             sink.write("first").await.unwrap();
             sink.write("second").await.unwrap();
         });
@@ -166,4 +224,29 @@ mod tests {
         );
         std::fs::remove_dir_all(&dir).ok();
     }
+
+    #[test]
+    fn sinks_sharing_a_path_take_turns() {
+        let dir = temp("shared");
+        let mut locks = WriteLocks::default();
+        let first = FileSink::new(&dir, "out/audit.json", &mut locks).unwrap();
+        let mut second = FileSink::new(&dir, "out/audit.json", &mut locks).unwrap();
+        let mut other = FileSink::new(&dir, "out/other.json", &mut locks).unwrap();
+
+        block_on(async {
+            // While one sink is mid-write (holding the path's turn), a second
+            // sink on the same path waits; a sink on another path doesn't.
+            let turn = first.lock.lock().await;
+            let wait = Duration::from_millis(50);
+            assert!(timeout(wait, second.write("B")).await.is_err());
+            assert!(timeout(wait, other.write("C")).await.is_ok());
+            drop(turn);
+            second.write("B").await.unwrap();
+        });
+        assert_eq!(
+            std::fs::read_to_string(dir.join("out/audit.json")).unwrap(),
+            "B"
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,438 @@
+//! The `sqlite` connector: rows of a table in a local SQLite database, read as
+//! JSON documents or inserted from them — a persistent store for edge
+//! deployments with no database server. Both ends resolve `path` against the
+//! connector root, like `file`.
+//!
+//! rusqlite is synchronous, so every statement runs on the blocking pool: the
+//! connection moves into a `spawn_blocking` task for the call and comes back
+//! with the result. A connector handles one document at a time, so there is
+//! never a second caller to share it with.
+
+use crate::connector::{MessageTooLarge, Sink, Source, SourceDoc};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{Connection, OpenFlags, params_from_iter};
+use serde_json::{Map, Value as Json};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+
+/// Rows fetched per query; the source holds at most this many in memory.
+const PAGE: usize = 256;
+/// How long a write waits for a database another pipeline is writing to.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Quote a table or column name for SQL, doubling any embedded `"`.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Run `call` against the connection on the blocking pool, then hand the
+/// connection back. A panic inside `call` loses the connection, and every
+/// later call says so rather than touching a half-used handle.
+async fn with_db<T: Send + 'static>(
+    slot: &mut Option<Connection>,
+    call: impl FnOnce(&Connection) -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let db = slot
+        .take()
+        .context("the connection was lost to an earlier failure")?;
+    let (db, result) = tokio::task::spawn_blocking(move || {
+        let result = call(&db);
+        (db, result)
+    })
+    .await
+    .context("sqlite task panicked")?;
+    *slot = Some(db);
+    result
+}
+
+/// Reads the rows of one table in `rowid` order, a page at a time, each row as
+/// a JSON object of its columns. A `where` condition narrows the rows; it is
+/// SQL from the project's own config, spliced in as written. A row over
+/// `max_bytes` is refused from the length of its column values, before its
+/// JSON is built.
+///
+/// `pub(crate)`: built only by `registry::build_source`, after the manifest
+/// gate has kept `path` inside the connector root.
+pub(crate) struct SqliteSource {
+    db: Option<Connection>,
+    /// `<path>:<table>`, the origin of every row before its rowid.
+    label: String,
+    query: String,
+    after: i64,
+    max_bytes: Option<usize>,
+    page: VecDeque<(i64, Fetched)>,
+    exhausted: bool,
+}
+
+impl SqliteSource {
+    /// Open the database read-only and prepare the query now, so a missing
+    /// file, a missing table, or a bad `where` fails at startup.
+    pub(crate) fn new(
+        root: &Path,
+        path: &str,
+        table: &str,
+        filter: Option<&str>,
+        max_bytes: Option<usize>,
+    ) -> Result<Self> {
+        let file = root.join(path);
+        let db = Connection::open_with_flags(&file, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("cannot open {}", file.display()))?;
+        let condition = filter.map(|w| format!(" AND ({w})")).unwrap_or_default();
+        let query = format!(
+            "SELECT rowid, * FROM {} WHERE rowid > ?1{condition} ORDER BY rowid LIMIT {PAGE}",
+            quote(table)
+        );
+        db.prepare_cached(&query)
+            .with_context(|| format!("cannot read table \"{table}\" in {path}"))?;
+        Ok(Self {
+            db: Some(db),
+            label: format!("{path}:{table}"),
+            query,
+            after: 0,
+            max_bytes,
+            page: VecDeque::new(),
+            exhausted: false,
+        })
+    }
+}
+
+/// A fetched row: its JSON text, or its size in bytes and the limit it is
+/// over. An oversized row stays in the page so the rows before it still go.
+type Fetched = std::result::Result<String, (u64, usize)>;
+
+/// The rows after `after`, as `(rowid, JSON text)`. A row whose text and blob
+/// values alone come to more than `max_bytes` is measured, not converted; its
+/// JSON (which adds names, quotes, and escapes) is held to the limit too.
+fn fetch(
+    db: &Connection,
+    query: &str,
+    after: i64,
+    max_bytes: Option<usize>,
+) -> Result<Vec<(i64, Fetched)>> {
+    let mut statement = db.prepare_cached(query)?;
+    let names: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let mut rows = statement.query([after])?;
+    let mut page = Vec::new();
+    while let Some(row) = rows.next()? {
+        let rowid: i64 = row.get(0)?;
+        if let Some(max) = max_bytes {
+            let mut size = 0;
+            for index in 1..names.len() {
+                size += match row.get_ref(index)? {
+                    ValueRef::Text(bytes) | ValueRef::Blob(bytes) => bytes.len() as u64,
+                    _ => 0,
+                };
+            }
+            if size > max as u64 {
+                page.push((rowid, Err((size, max))));
+                continue;
+            }
+        }
+        let mut object = Map::new();
+        // Column 0 is the rowid the query adds; the table's own columns follow.
+        for (index, name) in names.iter().enumerate().skip(1) {
+            let value = match row.get_ref(index)? {
+                ValueRef::Null => Json::Null,
+                ValueRef::Integer(n) => n.into(),
+                ValueRef::Real(x) => x.into(),
+                ValueRef::Text(text) => std::str::from_utf8(text)
+                    .with_context(|| format!("row {rowid}: column \"{name}\" is not UTF-8"))?
+                    .into(),
+                ValueRef::Blob(_) => {
+                    bail!("row {rowid}: column \"{name}\" is a BLOB, which has no JSON form")
+                }
+            };
+            object.insert(name.clone(), value);
+        }
+        let text = Json::Object(object).to_string();
+        let fetched = match max_bytes {
+            Some(max) if text.len() > max => Err((text.len() as u64, max)),
+            _ => Ok(text),
+        };
+        page.push((rowid, fetched));
+    }
+    Ok(page)
+}
+
+#[async_trait]
+impl Source for SqliteSource {
+    async fn next(&mut self) -> Result<Option<SourceDoc>> {
+        if self.page.is_empty() && !self.exhausted {
+            let (query, after, max) = (self.query.clone(), self.after, self.max_bytes);
+            let page = with_db(&mut self.db, move |db| fetch(db, &query, after, max)).await?;
+            self.exhausted = page.len() < PAGE;
+            self.page = page.into();
+        }
+        let Some((rowid, fetched)) = self.page.pop_front() else {
+            return Ok(None);
+        };
+        self.after = rowid;
+        let origin = format!("{}:{rowid}", self.label);
+        let payload = match fetched {
+            Ok(payload) => payload,
+            Err((size, max)) => return Err(MessageTooLarge { origin, size, max }.into()),
+        };
+        Ok(Some(SourceDoc { origin, payload }))
+    }
+}
+
+/// Inserts each document — a JSON object — as one row of an existing table,
+/// its fields as columns. Nested objects and arrays are stored as JSON text;
+/// booleans as 0/1, SQLite's own convention. A field with no column fails the
+/// document. Pipelines sharing a database each hold a connection, and SQLite's
+/// own locking (with a busy timeout) keeps their inserts whole.
+///
+/// `pub(crate)`: built only by `registry::build_sink`.
+pub(crate) struct SqliteSink {
+    db: Option<Connection>,
+    table: String,
+}
+
+impl SqliteSink {
+    /// Open the database and check the table exists now, so a typo in `path`
+    /// or `table` fails at startup rather than on the first document. The
+    /// table has to exist already, so the database is never created: a
+    /// mistyped path leaves no empty file behind.
+    pub(crate) fn new(root: &Path, path: &str, table: &str) -> Result<Self> {
+        let file = root.join(path);
+        let db = Connection::open_with_flags(&file, OpenFlags::SQLITE_OPEN_READ_WRITE)
+            .with_context(|| format!("cannot open {}", file.display()))?;
+        db.busy_timeout(BUSY_TIMEOUT)?;
+        db.prepare(&format!("SELECT * FROM {} LIMIT 0", quote(table)))
+            .with_context(|| format!("cannot write table \"{table}\" in {path}"))?;
+        Ok(Self {
+            db: Some(db),
+            table: table.to_string(),
+        })
+    }
+}
+
+/// The SQLite value a JSON field is stored as.
+fn to_sql(value: Json) -> Value {
+    match value {
+        Json::Null => Value::Null,
+        Json::Bool(b) => Value::Integer(b.into()),
+        Json::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Json::String(s) => Value::Text(s),
+        nested => Value::Text(nested.to_string()),
+    }
+}
+
+#[async_trait]
+impl Sink for SqliteSink {
+    async fn write(&mut self, payload: &str) -> Result<()> {
+        let Json::Object(fields) = serde_json::from_str(payload).context("not valid JSON")? else {
+            bail!("a sqlite row needs a JSON object document");
+        };
+        let table = quote(&self.table);
+        let sql = if fields.is_empty() {
+            format!("INSERT INTO {table} DEFAULT VALUES")
+        } else {
+            let columns: Vec<String> = fields.keys().map(|k| quote(k)).collect();
+            let slots: Vec<String> = (1..=fields.len()).map(|i| format!("?{i}")).collect();
+            format!(
+                "INSERT INTO {table} ({}) VALUES ({})",
+                columns.join(", "),
+                slots.join(", ")
+            )
+        };
+        let values: Vec<Value> = fields.into_iter().map(|(_, v)| to_sql(v)).collect();
+        with_db(&mut self.db, move |db| {
+            db.prepare_cached(&sql)?
+                .execute(params_from_iter(values))
+                .context("cannot insert the row")?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wv-sqlite-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Drive an async test body on a fresh current-thread runtime (the `macros`
+    /// feature is off, so there's no `#[tokio::test]`).
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn seed(dir: &Path, sql: &str) {
+        Connection::open(dir.join("shop.db"))
+            .unwrap()
+            .execute_batch(sql)
+            .unwrap();
+    }
+
+    #[test]
+    fn round_trips_records_through_a_table() {
+        let dir = temp("round-trip");
+        seed(
+            &dir,
+            "CREATE TABLE orders (id TEXT, qty INTEGER, price REAL, status TEXT, note TEXT);
+             INSERT INTO orders VALUES ('a-1', 2, 9.5, 'new', NULL);
+             INSERT INTO orders VALUES ('a-2', 1, 3.0, 'done', 'gift');
+             INSERT INTO orders VALUES ('a-3', 5, 0.25, 'new', 'rush');
+             CREATE TABLE shipped (id TEXT, qty INTEGER, rush INTEGER, tags TEXT);",
+        );
+
+        let read = block_on(async {
+            let mut source =
+                SqliteSource::new(&dir, "shop.db", "orders", Some("status = 'new'"), None).unwrap();
+            let mut sink = SqliteSink::new(&dir, "shop.db", "shipped").unwrap();
+            let mut origins = Vec::new();
+            while let Some(doc) = source.next().await.unwrap() {
+                let row: Json = serde_json::from_str(&doc.payload).unwrap();
+                let out = serde_json::json!({
+                    "id": row["id"],
+                    "qty": row["qty"],
+                    "rush": row["note"] == "rush",
+                    "tags": ["new"],
+                });
+                sink.write(&out.to_string()).await.unwrap();
+                origins.push(doc.origin);
+            }
+            assert_eq!(origins, ["shop.db:orders:1", "shop.db:orders:3"]);
+
+            let mut shipped = SqliteSource::new(&dir, "shop.db", "shipped", None, None).unwrap();
+            let mut read = Vec::new();
+            while let Some(doc) = shipped.next().await.unwrap() {
+                read.push(serde_json::from_str::<Json>(&doc.payload).unwrap());
+            }
+            read
+        });
+        assert_eq!(
+            read,
+            [
+                serde_json::json!({ "id": "a-1", "qty": 2, "rush": 0, "tags": "[\"new\"]" }),
+                serde_json::json!({ "id": "a-3", "qty": 5, "rush": 1, "tags": "[\"new\"]" }),
+            ]
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn source_reads_every_row_across_pages() {
+        let dir = temp("pages");
+        let rows = PAGE * 2 + 3;
+        let inserts: String = (0..rows)
+            .map(|i| format!("INSERT INTO n VALUES ({i});"))
+            .collect();
+        seed(&dir, &format!("CREATE TABLE n (i INTEGER); {inserts}"));
+        let count = block_on(async {
+            let mut source = SqliteSource::new(&dir, "shop.db", "n", None, None).unwrap();
+            let mut count = 0;
+            while source.next().await.unwrap().is_some() {
+                count += 1;
+            }
+            count
+        });
+        assert_eq!(count, rows);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn source_refuses_a_row_over_the_limit_and_keeps_its_place() {
+        let dir = temp("limit");
+        seed(
+            &dir,
+            &format!(
+                "CREATE TABLE notes (t TEXT);
+                 INSERT INTO notes VALUES ('ab');
+                 INSERT INTO notes VALUES ('{}');
+                 INSERT INTO notes VALUES ('123456789012345');
+                 INSERT INTO notes VALUES ('cd');",
+                "x".repeat(50)
+            ),
+        );
+        block_on(async {
+            let mut source = SqliteSource::new(&dir, "shop.db", "notes", None, Some(20)).unwrap();
+            assert_eq!(
+                source.next().await.unwrap().unwrap().payload,
+                r#"{"t":"ab"}"#
+            );
+            // Row 2's value alone is over; row 3's value fits but its JSON doesn't.
+            for (origin, size) in [("shop.db:notes:2", 50), ("shop.db:notes:3", 23)] {
+                let err = source.next().await.err().unwrap();
+                let refused = err
+                    .downcast_ref::<MessageTooLarge>()
+                    .expect("a MessageTooLarge");
+                assert_eq!((refused.origin.as_str(), refused.size), (origin, size));
+            }
+            assert_eq!(
+                source.next().await.unwrap().unwrap().payload,
+                r#"{"t":"cd"}"#
+            );
+        });
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn a_missing_table_fails_at_open_on_either_end() {
+        let dir = temp("missing");
+        seed(&dir, "CREATE TABLE orders (id TEXT);");
+        let err = SqliteSource::new(&dir, "shop.db", "ordrs", None, None)
+            .err()
+            .unwrap();
+        assert!(
+            format!("{err:#}").contains("cannot read table \"ordrs\""),
+            "{err:#}"
+        );
+        let err = SqliteSink::new(&dir, "shop.db", "shiped").err().unwrap();
+        assert!(
+            format!("{err:#}").contains("no such table: shiped"),
+            "{err:#}"
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn a_sink_with_a_mistyped_path_fails_without_creating_a_file() {
+        let dir = temp("stray");
+        seed(&dir, "CREATE TABLE shipped (id TEXT);");
+        for path in ["shpo.db", "nested/shop.db"] {
+            let err = SqliteSink::new(&dir, path, "shipped").err().unwrap();
+            assert!(format!("{err:#}").contains("cannot open"), "{err:#}");
+        }
+        assert!(!dir.join("shpo.db").exists());
+        assert!(!dir.join("nested").exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn sink_refuses_a_document_that_is_not_an_object_or_has_no_column() {
+        let dir = temp("refuse");
+        seed(&dir, "CREATE TABLE shipped (id TEXT);");
+        block_on(async {
+            let mut sink = SqliteSink::new(&dir, "shop.db", "shipped").unwrap();
+            let err = sink.write("[1, 2]").await.unwrap_err().to_string();
+            assert!(err.contains("needs a JSON object"), "{err}");
+            let err = format!("{:#}", sink.write(r#"{"sku": "x"}"#).await.unwrap_err());
+            assert!(err.contains("no column named sku"), "{err}");
+            // The failed insert left the connection usable.
+            sink.write(r#"{"id": "a-1"}"#).await.unwrap();
+        });
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -39,6 +39,10 @@ pub struct ResultEnvelope {
     pub ok: bool,
     pub payload: Option<String>,
     pub error: Option<EnvelopeError>,
+    /// The flow's output was empty and its `onEmptyOutput: skip` policy
+    /// dropped it: an ok envelope with nothing to write.
+    #[serde(default)]
+    pub skipped: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,6 +58,13 @@ struct HostState {
     limits: StoreLimits,
 }
 
+/// One document through a flow: input envelope in, result envelope out.
+/// [`FlowModule`] is the production implementation; the trait is the run
+/// loop's seam, so its policies can be tested without a compiled artifact.
+pub trait Transform: Send + Sync {
+    fn run(&self, input: &InputEnvelope<'_>) -> Result<ResultEnvelope>;
+}
+
 /// A compiled flow module, reusable across documents and threads.
 pub struct FlowModule {
     engine: Arc<Engine>,
@@ -107,10 +118,10 @@ impl Host {
     }
 }
 
-impl FlowModule {
+impl Transform for FlowModule {
     /// Run one document through the flow: fresh store, write the input
     /// envelope to stdin, run `_start`, parse the result envelope from stdout.
-    pub fn run(&self, input: &InputEnvelope<'_>) -> Result<ResultEnvelope> {
+    fn run(&self, input: &InputEnvelope<'_>) -> Result<ResultEnvelope> {
         let stdin = serde_json::to_string(input).context("encode input envelope")?;
         let stdout = MemoryOutputPipe::new(STDOUT_CAP_BYTES);
 
@@ -171,6 +182,12 @@ mod tests {
         let ok: ResultEnvelope = serde_json::from_str(r#"{"ok":true,"payload":"x"}"#).unwrap();
         assert!(ok.ok);
         assert_eq!(ok.payload.as_deref(), Some("x"));
+        assert!(!ok.skipped);
+
+        let skipped: ResultEnvelope =
+            serde_json::from_str(r#"{"ok":true,"skipped":true}"#).unwrap();
+        assert!(skipped.ok && skipped.skipped);
+        assert!(skipped.payload.is_none());
 
         let err: ResultEnvelope = serde_json::from_str(
             r#"{"ok":false,"error":{"stage":"parse","type":"JsonParseError","message":"bad"}}"#,
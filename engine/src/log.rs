@@ -3,7 +3,17 @@
 //! (just serde_json, already a dependency); a tracing stack can replace this
 //! when the engine grows subscribers.
 
+use crate::metrics::Counts;
 use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Set once at boot by `--quiet`: info lines are dropped, warnings and errors kept.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
 
 pub fn done(pipeline: &str, document: usize) {
     emit(
@@ -11,12 +21,39 @@ pub fn done(pipeline: &str, document: usize) {
     );
 }
 
+pub fn skipped_empty(pipeline: &str, document: usize) {
+    emit(
+        json!({ "level": "warn", "event": "document", "pipeline": pipeline, "document": document, "status": "skipped", "reason": "empty_output" }),
+    );
+}
+
 pub fn error(pipeline: &str, document: usize, stage: &str, error_type: &str, message: &str) {
     emit(
         json!({ "level": "error", "event": "document", "pipeline": pipeline, "document": document, "stage": stage, "type": error_type, "message": message }),
     );
 }
 
+pub fn pull_timeout(pipeline: &str, connector: &str, strike: u32, waited: Duration) {
+    emit(
+        json!({ "level": "warn", "event": "pull_timeout", "pipeline": pipeline, "connector": connector, "strike": strike, "waited_ms": waited.as_millis() as u64 }),
+    );
+}
+
+pub fn max_runtime(pipeline: &str, documents: usize) {
+    emit(
+        json!({ "level": "info", "event": "max_runtime", "pipeline": pipeline, "documents": documents, "status": "drained" }),
+    );
+}
+
+pub fn connector_metrics(pipeline: &str, pulled: Counts, pushed: Counts) {
+    emit(
+        json!({ "level": "info", "event": "connector_metrics", "pipeline": pipeline, "source_messages": pulled.messages, "source_bytes": pulled.bytes, "source_timeouts": pulled.timeouts, "sink_messages": pushed.messages, "sink_bytes": pushed.bytes }),
+    );
+}
+
 fn emit(record: serde_json::Value) {
+    if QUIET.load(Ordering::Relaxed) && record["level"] == "info" {
+        return;
+    }
     eprintln!("{record}");
 }
@@ -15,28 +15,59 @@ mod connectors;
 mod host;
 mod log;
 mod manifest;
+mod metrics;
 mod registry;
 mod runner;
+mod summary;
+mod throttle;
 
-use std::path::Path;
 use std::process::ExitCode;
+use std::time::Instant;
 
-async fn run(artifact_dir: &Path) -> anyhow::Result<bool> {
-    let manifest = manifest::load(artifact_dir)?;
-    let report = runner::run(artifact_dir, &manifest).await?;
+async fn run(boot: &config::Boot) -> anyhow::Result<bool> {
+    let started = Instant::now();
+    let manifest = manifest::load(&boot.artifact)?;
+    let report = runner::run(&boot.artifact, &manifest, &boot.options).await?;
 
+    for (pipeline, tally) in &report.completed {
+        let count = tally.documents;
+        let plural = if count == 1 { "" } else { "s" };
+        let skipped = skipped_note(tally.skipped);
+        let drained = if tally.drained {
+            ", stopped at --max-runtime"
+        } else {
+            ""
+        };
+        eprintln!("✓ {pipeline} ({count} document{plural}{skipped}{drained})");
+    }
     for (pipeline, error) in &report.failures {
         eprintln!("✗ {pipeline}: {error}");
     }
+    for (pipeline, traffic) in &report.throughput {
+        log::connector_metrics(pipeline, traffic.pulled, traffic.pushed);
+    }
     let total = manifest.pipelines.len();
     let ran = total - report.failures.len();
     eprintln!(
-        "{ran}/{total} pipelines ran ({} documents)",
-        report.documents
+        "{ran}/{total} pipelines ran ({} documents{})",
+        report.documents,
+        skipped_note(report.skipped)
     );
+    if boot.output == config::Output::Json {
+        let summary = summary::Summary::new(&report, started.elapsed());
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    }
     Ok(report.failures.is_empty())
 }
 
+/// `", N skipped as empty"` for a summary line, or nothing when none were.
+fn skipped_note(skipped: usize) -> String {
+    match skipped {
+        0 => String::new(),
+        n => format!(", {n} skipped as empty"),
+    }
+}
+
 fn main() -> ExitCode {
     let boot = match config::parse(std::env::args().skip(1)) {
         Ok(config::Cli::Run(boot)) => boot,
@@ -68,7 +99,8 @@ fn main() -> ExitCode {
         }
     };
 
-    match runtime.block_on(run(&boot.artifact)) {
+    log::set_quiet(boot.quiet);
+    match runtime.block_on(run(&boot)) {
         Ok(true) => ExitCode::SUCCESS,
         Ok(false) => ExitCode::FAILURE,
         Err(err) => {
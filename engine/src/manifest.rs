@@ -7,12 +7,15 @@
 
 use anyhow::{Context, Result, bail};
 use serde::Deserialize;
+use std::num::NonZeroU32;
 use std::path::Path;
 
 /// The manifest file shape this engine understands.
 pub const MANIFEST_VERSION: &str = "1";
 /// The wasm host ABI this engine can drive (Javy stdin/stdout).
 pub const ABI_VERSION: &str = "javy-1";
+/// Document formats the flow modules' format packs parse and serialize.
+const FORMATS: [&str; 2] = ["json", "xml"];
 
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
@@ -30,22 +33,89 @@ pub struct Pipeline {
     /// Flow name; resolves by convention to `flows/<flow>.wasm`.
     pub flow: String,
     pub sink: SinkSpec,
+    /// Most documents per second the pipeline may pull; unlimited when absent.
+    #[serde(default, rename = "rateLimit")]
+    pub rate_limit: Option<NonZeroU32>,
 }
 
+/// Where a pipeline reads documents from, keyed by connector `type`. An
+/// unknown `type` fails to deserialize, naming the types that exist.
 #[derive(Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct SourceSpec {
-    pub r#type: String,
-    pub glob: String,
-    pub format: String,
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum SourceSpec {
+    /// Each file `glob` matches is one document.
+    File { glob: String, format: String },
+    /// Each row of `table` in the SQLite database at `path` is one JSON
+    /// document, optionally narrowed by a SQL `where` condition.
+    Sqlite {
+        path: String,
+        table: String,
+        #[serde(rename = "where")]
+        filter: Option<String>,
+        format: String,
+    },
 }
 
+/// Where a pipeline writes documents to, keyed by connector `type`.
 #[derive(Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct SinkSpec {
-    pub r#type: String,
-    pub path: String,
-    pub format: String,
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum SinkSpec {
+    /// Each document overwrites the file at `path`.
+    File { path: String, format: String },
+    /// Each document (a JSON object) is inserted as one row of `table`, its
+    /// fields as columns.
+    Sqlite {
+        path: String,
+        table: String,
+        format: String,
+    },
+}
+
+impl SourceSpec {
+    /// The connector `type`, for errors and logs.
+    pub fn connector(&self) -> &'static str {
+        match self {
+            Self::File { .. } => "file",
+            Self::Sqlite { .. } => "sqlite",
+        }
+    }
+
+    pub fn format(&self) -> &str {
+        match self {
+            Self::File { format, .. } | Self::Sqlite { format, .. } => format,
+        }
+    }
+
+    /// The root-relative path the connector reads, and what to call it in errors.
+    fn location(&self) -> (&'static str, &str) {
+        match self {
+            Self::File { glob, .. } => ("source glob", glob),
+            Self::Sqlite { path, .. } => ("source path", path),
+        }
+    }
+}
+
+impl SinkSpec {
+    /// The connector `type`, for errors and logs.
+    pub fn connector(&self) -> &'static str {
+        match self {
+            Self::File { .. } => "file",
+            Self::Sqlite { .. } => "sqlite",
+        }
+    }
+
+    pub fn format(&self) -> &str {
+        match self {
+            Self::File { format, .. } | Self::Sqlite { format, .. } => format,
+        }
+    }
+
+    /// The root-relative path the connector writes, and what to call it in errors.
+    fn location(&self) -> (&'static str, &str) {
+        match self {
+            Self::File { path, .. } | Self::Sqlite { path, .. } => ("sink path", path),
+        }
+    }
 }
 
 /// Parse and validate a manifest from JSON text.
@@ -72,12 +142,22 @@ pub fn parse(text: &str) -> Result<Manifest> {
         bail!("manifest has no pipelines");
     }
     for pipeline in &manifest.pipelines {
-        // Connector `type` is validated when the registry builds it (E4); here
-        // we guard the path shape regardless of type. Every path in the
+        // Connector `type` was checked by deserializing into the spec enums;
+        // here we guard the path shape regardless of type. Every path in the
         // manifest resolves against the artifact root, so an absolute path or a
         // `..` component would silently escape it.
-        check_contained(&pipeline.name, "source glob", &pipeline.source.glob)?;
-        check_contained(&pipeline.name, "sink path", &pipeline.sink.path)?;
+        let (field, path) = pipeline.source.location();
+        check_contained(&pipeline.name, field, path)?;
+        let (field, path) = pipeline.sink.location();
+        check_contained(&pipeline.name, field, path)?;
+        check_format(&pipeline.name, "source", pipeline.source.format())?;
+        check_format(&pipeline.name, "sink", pipeline.sink.format())?;
+        if let SourceSpec::Sqlite { table, .. } = &pipeline.source {
+            check_sqlite(&pipeline.name, "source", table, pipeline.source.format())?;
+        }
+        if let SinkSpec::Sqlite { table, .. } = &pipeline.sink {
+            check_sqlite(&pipeline.name, "sink", table, pipeline.sink.format())?;
+        }
         if pipeline.flow.is_empty() || pipeline.flow.contains(['/', '\\']) || pipeline.flow == ".."
         {
             bail!(
@@ -108,6 +188,27 @@ fn check_contained(pipeline: &str, field: &str, path: &str) -> Result<()> {
     Ok(())
 }
 
+/// Refuse a format no flow module can handle, so a hand-edited manifest fails
+/// at boot instead of on every document.
+fn check_format(pipeline: &str, end: &str, format: &str) -> Result<()> {
+    if !FORMATS.contains(&format) {
+        bail!("pipeline \"{pipeline}\": {end} format \"{format}\" is not supported (json or xml)");
+    }
+    Ok(())
+}
+
+/// A SQLite row is a JSON object, so both ends of a `sqlite` connector speak
+/// JSON; and a table has to be named.
+fn check_sqlite(pipeline: &str, end: &str, table: &str, format: &str) -> Result<()> {
+    if table.is_empty() {
+        bail!("pipeline \"{pipeline}\": sqlite {end} table is empty");
+    }
+    if format != "json" {
+        bail!("pipeline \"{pipeline}\": sqlite {end} format must be json (rows are JSON objects)");
+    }
+    Ok(())
+}
+
 /// Load `manifest.json` from an artifact directory.
 pub fn load(artifact_dir: &Path) -> Result<Manifest> {
     let path = artifact_dir.join("manifest.json");
@@ -138,8 +239,72 @@ mod tests {
         let m = parse(GOLDEN).expect("golden manifest parses");
         assert_eq!(m.pipelines.len(), 1);
         assert_eq!(m.pipelines[0].flow, "order");
-        assert_eq!(m.pipelines[0].source.glob, "in/*.json");
-        assert_eq!(m.pipelines[0].sink.format, "json");
+        assert!(
+            matches!(&m.pipelines[0].source, SourceSpec::File { glob, .. } if glob == "in/*.json")
+        );
+        assert_eq!(m.pipelines[0].sink.format(), "json");
+    }
+
+    #[test]
+    fn parses_a_sqlite_source_and_sink() {
+        let text = GOLDEN
+            .replace(
+                r#"{ "type": "file", "glob": "in/*.json", "format": "json" }"#,
+                r#"{ "type": "sqlite", "path": "data/shop.db", "table": "orders", "where": "status = 'new'", "format": "json" }"#,
+            )
+            .replace(
+                r#"{ "type": "file", "path": "out/order.json", "format": "json" }"#,
+                r#"{ "type": "sqlite", "path": "data/shop.db", "table": "shipped", "format": "json" }"#,
+            );
+        let m = parse(&text).expect("sqlite manifest parses");
+        let SourceSpec::Sqlite { table, filter, .. } = &m.pipelines[0].source else {
+            panic!("expected a sqlite source");
+        };
+        assert_eq!(table, "orders");
+        assert_eq!(filter.as_deref(), Some("status = 'new'"));
+        assert_eq!(m.pipelines[0].sink.connector(), "sqlite");
+    }
+
+    #[test]
+    fn refuses_an_unknown_connector_type_naming_the_known_ones() {
+        let text = GOLDEN.replace(r#""type": "file", "glob""#, r#""type": "rest", "glob""#);
+        let err = format!("{:#}", parse(&text).unwrap_err());
+        assert!(
+            err.contains("unknown variant `rest`, expected `file` or `sqlite`"),
+            "{err}"
+        );
+        let text = GOLDEN.replace(r#""type": "file", "path""#, r#""type": "blob", "path""#);
+        let err = format!("{:#}", parse(&text).unwrap_err());
+        assert!(err.contains("unknown variant `blob`"), "{err}");
+    }
+
+    #[test]
+    fn refuses_a_sqlite_connector_that_is_not_json() {
+        let text = GOLDEN.replace(
+            r#"{ "type": "file", "path": "out/order.json", "format": "json" }"#,
+            r#"{ "type": "sqlite", "path": "data/shop.db", "table": "shipped", "format": "xml" }"#,
+        );
+        let err = parse(&text).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "pipeline \"orders\": sqlite sink format must be json (rows are JSON objects)"
+        );
+    }
+
+    #[test]
+    fn parses_a_rate_limit_and_refuses_zero() {
+        let limited = |limit: &str| {
+            GOLDEN.replace(
+                r#""flow": "order","#,
+                &format!(r#""flow": "order", "rateLimit": {limit},"#),
+            )
+        };
+        let m = parse(&limited("50")).expect("rate-limited manifest parses");
+        assert_eq!(m.pipelines[0].rate_limit, NonZeroU32::new(50));
+        assert_eq!(parse(GOLDEN).unwrap().pipelines[0].rate_limit, None);
+
+        let err = format!("{:#}", parse(&limited("0")).unwrap_err());
+        assert!(err.contains("nonzero"), "{err}");
     }
 
     #[test]
@@ -183,6 +348,25 @@ mod tests {
         assert!(err.contains("must not contain \"..\""), "{err}");
     }
 
+    #[test]
+    fn refuses_an_unsupported_format_naming_the_field() {
+        let text = GOLDEN.replacen("\"format\": \"json\"", "\"format\": \"parquet\"", 1);
+        let err = parse(&text).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "pipeline \"orders\": source format \"parquet\" is not supported (json or xml)"
+        );
+        let text = GOLDEN.replace(
+            "\"path\": \"out/order.json\", \"format\": \"json\"",
+            "\"path\": \"out/order.csv\", \"format\": \"csv\"",
+        );
+        let err = parse(&text).unwrap_err().to_string();
+        assert!(
+            err.contains("sink format \"csv\" is not supported"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn refuses_a_flow_name_with_a_path_separator() {
         let text = GOLDEN.replace("\"flow\": \"order\"", "\"flow\": \"../order\"");
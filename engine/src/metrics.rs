@@ -0,0 +1,53 @@
+//! Per-connector throughput: messages and bytes each pipeline's source pulled
+//! and its sink pushed. The registry's wrapping layer records into these, so
+//! every connector is counted the same way without touching its own code; the
+//! run loop only carries the handles, counts the source's pull timeouts, and
+//! reports a snapshot at the end.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Live counters for one connector instance, shared between the connector
+/// (which records) and the runner (which snapshots).
+#[derive(Debug, Default)]
+pub struct Throughput {
+    messages: AtomicU64,
+    bytes: AtomicU64,
+    timeouts: AtomicU64,
+}
+
+/// A point-in-time copy of a [`Throughput`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Counts {
+    pub messages: u64,
+    pub bytes: u64,
+    /// `--pull-timeout-ms` deadlines the source missed; left out of the JSON
+    /// when there were none (always, for a sink).
+    #[serde(skip_serializing_if = "is_zero")]
+    pub timeouts: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl Throughput {
+    /// Count one message of `bytes` bytes.
+    pub fn record(&self, bytes: usize) {
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Count one pull that ran past its deadline.
+    pub fn record_timeout(&self) {
+        self.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> Counts {
+        Counts {
+            messages: self.messages.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+        }
+    }
+}
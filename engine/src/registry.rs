@@ -1,34 +1,118 @@
 //! Connector registry (Engine Plan E4): maps a manifest connector `type` to a
-//! concrete [`Source`]/[`Sink`]. This is the single place that knows which
-//! connector types exist, so adding one is a new match arm here plus its
-//! module under `connectors/` — the run loop never changes.
-//!
-//! TODO(next connector): the manifest specs ([`SourceSpec`]/[`SinkSpec`]) are
-//! still file-shaped (`glob`/`path`) with `deny_unknown_fields`, so a manifest
-//! with `"type": "rest"` fails to deserialize before it reaches this registry.
-//! The first non-`file` connector must turn those flat structs into a
-//! `#[serde(tag = "type")]` enum — do that rather than bolting on `Option<_>`
-//! fields.
-
-use crate::connector::{Sink, Source};
-use crate::connectors::file::{FileSink, FileSource};
+//! concrete [`Source`]/[`Sink`]. Adding a connector is a variant on the
+//! manifest's [`SourceSpec`]/[`SinkSpec`] (which is what rejects an unknown
+//! `type`), a match arm here, and its module under `connectors/` — the run
+//! loop never changes. Every connector it builds comes back wrapped so its
+//! failures surface as a [`ConnectorError`] naming the connector type and
+//! operation, and so every document it moves is counted in a [`Throughput`].
+
+use crate::connector::{ConnectorError, MessageTooLarge, Sink, Source, SourceDoc};
+use crate::connectors::file::{FileSink, FileSource, WriteLocks};
+use crate::connectors::sqlite::{SqliteSink, SqliteSource};
 use crate::manifest::{SinkSpec, SourceSpec};
-use anyhow::{Result, bail};
+use crate::metrics::Throughput;
+use anyhow::Result;
+use async_trait::async_trait;
 use std::path::Path;
+use std::sync::Arc;
 
 /// Build the source for a pipeline, resolving paths against the connector root.
-pub fn build_source(root: &Path, spec: &SourceSpec) -> Result<Box<dyn Source>> {
-    match spec.r#type.as_str() {
-        "file" => Ok(Box::new(FileSource::new(root, &spec.glob)?)),
-        other => bail!("unknown source type \"{other}\" (only \"file\" is supported)"),
-    }
+/// Each document it yields is counted in `pulled`; one over `max_bytes` is
+/// refused as a [`MessageTooLarge`] before it is read.
+pub fn build_source(
+    root: &Path,
+    spec: &SourceSpec,
+    pulled: Arc<Throughput>,
+    max_bytes: Option<usize>,
+) -> Result<Box<dyn Source>> {
+    let built: Result<Box<dyn Source>> = match spec {
+        SourceSpec::File { glob, .. } => {
+            FileSource::new(root, glob, max_bytes).map(|s| Box::new(s) as _)
+        }
+        SourceSpec::Sqlite {
+            path,
+            table,
+            filter,
+            ..
+        } => SqliteSource::new(root, path, table, filter.as_deref(), max_bytes)
+            .map(|s| Box::new(s) as _),
+    };
+    let inner = built.map_err(|e| ConnectorError::wrap(spec.connector(), "open", e))?;
+    Ok(Box::new(Named {
+        connector: spec.connector().to_string(),
+        inner,
+        throughput: pulled,
+    }))
 }
 
 /// Build the sink for a pipeline, resolving paths against the connector root.
-pub fn build_sink(root: &Path, spec: &SinkSpec) -> Result<Box<dyn Sink>> {
-    match spec.r#type.as_str() {
-        "file" => Ok(Box::new(FileSink::new(root, &spec.path)?)),
-        other => bail!("unknown sink type \"{other}\" (only \"file\" is supported)"),
+/// Each document it writes is counted in `pushed`. Sinks built against the
+/// same [`Sinks`] that write to one destination serialize their writes.
+pub fn build_sink(
+    root: &Path,
+    spec: &SinkSpec,
+    pushed: Arc<Throughput>,
+    shared: &mut Sinks,
+) -> Result<Box<dyn Sink>> {
+    let built: Result<Box<dyn Sink>> = match spec {
+        SinkSpec::File { path, .. } => {
+            FileSink::new(root, path, &mut shared.files).map(|s| Box::new(s) as _)
+        }
+        SinkSpec::Sqlite { path, table, .. } => {
+            SqliteSink::new(root, path, table).map(|s| Box::new(s) as _)
+        }
+    };
+    let inner = built.map_err(|e| ConnectorError::wrap(spec.connector(), "open", e))?;
+    Ok(Box::new(Named {
+        connector: spec.connector().to_string(),
+        inner,
+        throughput: pushed,
+    }))
+}
+
+/// State shared by the sinks of one run: whatever lets connectors coordinate
+/// pipelines writing to the same destination.
+#[derive(Default)]
+pub struct Sinks {
+    files: WriteLocks,
+}
+
+/// A built connector tagged with its type, turning its errors into
+/// [`ConnectorError`]s and counting what it moves, so every connector reports
+/// failures and throughput the same way.
+struct Named<T: ?Sized> {
+    connector: String,
+    inner: Box<T>,
+    throughput: Arc<Throughput>,
+}
+
+#[async_trait]
+impl Source for Named<dyn Source> {
+    async fn next(&mut self) -> Result<Option<SourceDoc>> {
+        // An oversized document is not the connector failing; it passes through.
+        let doc = self.inner.next().await.map_err(|e| {
+            if e.is::<MessageTooLarge>() {
+                e
+            } else {
+                ConnectorError::wrap(&self.connector, "next", e)
+            }
+        })?;
+        if let Some(doc) = &doc {
+            self.throughput.record(doc.payload.len());
+        }
+        Ok(doc)
+    }
+}
+
+#[async_trait]
+impl Sink for Named<dyn Sink> {
+    async fn write(&mut self, payload: &str) -> Result<()> {
+        self.inner
+            .write(payload)
+            .await
+            .map_err(|e| ConnectorError::wrap(&self.connector, "write", e))?;
+        self.throughput.record(payload.len());
+        Ok(())
     }
 }
 
@@ -36,32 +120,134 @@ pub fn build_sink(root: &Path, spec: &SinkSpec) -> Result<Box<dyn Sink>> {
 mod tests {
     use super::*;
     use crate::manifest::{SinkSpec, SourceSpec};
+    use crate::metrics::Counts;
 
     #[test]
-    fn rejects_an_unknown_source_type() {
-        let spec = SourceSpec {
-            r#type: "rest".into(),
-            glob: "in/*.json".into(),
+    fn a_sqlite_source_that_cannot_open_is_a_connector_error_naming_sqlite() {
+        let spec = SourceSpec::Sqlite {
+            path: "data/missing.db".into(),
+            table: "orders".into(),
+            filter: None,
             format: "json".into(),
         };
-        let err = build_source(Path::new("/tmp"), &spec)
+        let err = build_source(Path::new("/nonexistent"), &spec, Arc::default(), None)
             .err()
-            .unwrap()
-            .to_string();
-        assert!(err.contains("unknown source type \"rest\""), "{err}");
+            .unwrap();
+        let connector = err
+            .downcast_ref::<ConnectorError>()
+            .expect("a ConnectorError");
+        assert_eq!(connector.connector, "sqlite");
+        assert_eq!(connector.operation, "open");
     }
 
     #[test]
-    fn rejects_an_unknown_sink_type() {
-        let spec = SinkSpec {
-            r#type: "blob".into(),
+    fn a_file_write_failure_is_a_connector_error_naming_the_connector() {
+        let root = std::env::temp_dir().join(format!("wv-registry-{}", std::process::id()));
+        let spec = SinkSpec::File {
             path: "out/x.json".into(),
             format: "json".into(),
         };
-        let err = build_sink(Path::new("/tmp"), &spec)
+        let mut sink = build_sink(&root, &spec, Arc::default(), &mut Sinks::default()).unwrap();
+        // A directory where the sink's file should be makes the write fail.
+        std::fs::create_dir_all(root.join("out/x.json")).unwrap();
+
+        let err = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(sink.write("{}"))
+            .unwrap_err();
+        let connector = err
+            .downcast_ref::<ConnectorError>()
+            .expect("a ConnectorError");
+        assert_eq!(connector.connector, "file");
+        assert_eq!(connector.operation, "write");
+        assert!(connector.message.contains("cannot write"), "{err}");
+        assert!(
+            err.to_string().starts_with("file connector write failed:"),
+            "{err}"
+        );
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn an_oversized_document_passes_through_unwrapped() {
+        let root = std::env::temp_dir().join(format!("wv-registry-big-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("in")).unwrap();
+        std::fs::write(root.join("in/a.json"), "{\"a\":1}").unwrap();
+        let spec = SourceSpec::File {
+            glob: "in/*.json".into(),
+            format: "json".into(),
+        };
+        let pulled = Arc::new(Throughput::default());
+        let mut source = build_source(&root, &spec, Arc::clone(&pulled), Some(4)).unwrap();
+
+        let err = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(source.next())
+            .err()
+            .unwrap();
+        assert!(err.is::<MessageTooLarge>(), "{err}");
+        assert_eq!(pulled.snapshot().messages, 0);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn a_source_that_cannot_open_is_a_connector_error() {
+        let spec = SourceSpec::File {
+            glob: "in/*.json".into(),
+            format: "json".into(),
+        };
+        let err = build_source(Path::new("/nonexistent"), &spec, Arc::default(), None)
             .err()
+            .unwrap();
+        let connector = err
+            .downcast_ref::<ConnectorError>()
+            .expect("a ConnectorError");
+        assert_eq!(connector.operation, "open");
+        assert!(connector.message.contains("matched no files"), "{err}");
+    }
+
+    #[test]
+    fn the_wrapping_layer_counts_messages_and_bytes_both_ways() {
+        let root = std::env::temp_dir().join(format!("wv-metrics-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("in")).unwrap();
+        std::fs::write(root.join("in/a.json"), "{\"a\":1}").unwrap();
+        std::fs::write(root.join("in/b.json"), "{}").unwrap();
+        let pulled = Arc::new(Throughput::default());
+        let pushed = Arc::new(Throughput::default());
+        let source_spec = SourceSpec::File {
+            glob: "in/*.json".into(),
+            format: "json".into(),
+        };
+        let sink_spec = SinkSpec::File {
+            path: "out/x.json".into(),
+            format: "json".into(),
+        };
+        let mut source = build_source(&root, &source_spec, Arc::clone(&pulled), None).unwrap();
+        let mut sink = build_sink(
+            &root,
+            &sink_spec,
+            Arc::clone(&pushed),
+            &mut Sinks::default(),
+        )
+        .unwrap();
+
+        tokio::runtime::Builder::new_current_thread()
+            .build()
             .unwrap()
-            .to_string();
-        assert!(err.contains("unknown sink type \"blob\""), "{err}");
+            .block_on(async {
+                while let Some(doc) = source.next().await.unwrap() {
+                    sink.write(&doc.payload).await.unwrap();
+                }
+            });
+        let moved = Counts {
+            messages: 2,
+            bytes: 9,
+            timeouts: 0,
+        };
+        assert_eq!(pulled.snapshot(), moved);
+        assert_eq!(pushed.snapshot(), moved);
+        std::fs::remove_dir_all(&root).ok();
     }
 }
@@ -6,40 +6,107 @@
 //! transform is synchronous and runs in `spawn_blocking`. Error scoping per
 //! RFC 0002/0003: startup errors abort the run; per-document failures fail a
 //! bounded run and would log-and-move-on on a live stream (every source this
-//! phase is bounded — files).
+//! phase is bounded — files and sqlite tables).
 
-use crate::connector::{Sink, Source};
-use crate::host::{FlowModule, Host, InputEnvelope};
+use crate::connector::{MessageTooLarge, Sink, Source, SourceDoc};
+use crate::host::{Host, InputEnvelope, Transform};
 use crate::log;
 use crate::manifest::Manifest;
+use crate::metrics::{Counts, Throughput};
 use crate::registry;
+use crate::throttle::{RateLimit, Throttle};
 use anyhow::{Context, Result, bail};
 use std::collections::HashMap;
+use std::num::NonZeroU32;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::task::JoinSet;
 
+/// Consecutive pull deadlines a source may miss before its pipeline fails.
+const PULL_STRIKES: u32 = 3;
+
+/// Run-loop knobs set from the command line (`config::parse`). Every field
+/// defaults to "off", so a bare `weavster-engine` behaves as before.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Refuse a document whose payload is larger than this many bytes. The
+    /// source checks it before reading the payload (`MessageTooLarge`).
+    pub max_message_bytes: Option<usize>,
+    /// Stop each pipeline after its first document (a per-pipeline smoke run).
+    pub once_per_pipeline: bool,
+    /// Warn when a source takes longer than this to yield a document; fail
+    /// the pipeline after `PULL_STRIKES` misses in a row.
+    pub pull_timeout: Option<Duration>,
+    /// Stop pulling once this long has passed since the pipeline started; a
+    /// pull already under way and the documents already pulled still finish,
+    /// and the run counts as a success.
+    pub max_runtime: Option<Duration>,
+}
+
+/// What one pipeline moved: documents pulled from its source, how many of
+/// those the flow's `onEmptyOutput: skip` policy dropped instead of writing,
+/// whether `--max-runtime` stopped it before its source ran dry, and — for a
+/// pipeline with a `rateLimit` — the limit, how long it waited on it, and how
+/// long it ran.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    pub documents: usize,
+    pub skipped: usize,
+    pub drained: bool,
+    pub throttled: Option<Throttle>,
+}
+
 pub struct RunReport {
+    /// Pipeline name → tally, for pipelines that ran to completion, in
+    /// manifest declaration order.
+    pub completed: Vec<(String, Tally)>,
     /// Pipeline name → error message, for pipelines that failed.
     pub failures: Vec<(String, String)>,
     pub documents: usize,
+    pub skipped: usize,
+    /// Pipeline name → what its source pulled and its sink pushed, for every
+    /// pipeline (failed ones included), in manifest declaration order.
+    pub throughput: Vec<(String, Traffic)>,
+}
+
+/// A pipeline's connector throughput at the end of a run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Traffic {
+    pub pulled: Counts,
+    pub pushed: Counts,
 }
 
 /// Load every flow the manifest references (deduplicated), then run all
 /// pipelines concurrently. The connector root is the artifact directory.
-pub async fn run(artifact_dir: &Path, manifest: &Manifest) -> Result<RunReport> {
+pub async fn run(artifact_dir: &Path, manifest: &Manifest, options: &Options) -> Result<RunReport> {
     let host = Host::new()?;
-    let mut flows: HashMap<String, Arc<FlowModule>> = HashMap::new();
+    let mut flows: HashMap<String, Arc<dyn Transform>> = HashMap::new();
 
     // Startup, in declaration order: build each pipeline's connectors (which
     // validates the connector type and opens the source) and load its flow
     // module. Any failure here aborts the whole run before a document moves.
     let mut plans = Vec::with_capacity(manifest.pipelines.len());
+    let mut counters = Vec::with_capacity(manifest.pipelines.len());
+    let mut sinks = registry::Sinks::default();
     for pipeline in &manifest.pipelines {
-        let source = registry::build_source(artifact_dir, &pipeline.source)
-            .with_context(|| format!("pipeline \"{}\" source", pipeline.name))?;
-        let sink = registry::build_sink(artifact_dir, &pipeline.sink)
-            .with_context(|| format!("pipeline \"{}\" sink", pipeline.name))?;
+        let pulled = Arc::new(Throughput::default());
+        let pushed = Arc::new(Throughput::default());
+        let source = registry::build_source(
+            artifact_dir,
+            &pipeline.source,
+            Arc::clone(&pulled),
+            options.max_message_bytes,
+        )
+        .with_context(|| format!("pipeline \"{}\" source", pipeline.name))?;
+        let sink = registry::build_sink(
+            artifact_dir,
+            &pipeline.sink,
+            Arc::clone(&pushed),
+            &mut sinks,
+        )
+        .with_context(|| format!("pipeline \"{}\" sink", pipeline.name))?;
+        counters.push((pipeline.name.clone(), Arc::clone(&pulled), pushed));
         if !flows.contains_key(&pipeline.flow) {
             let module = host
                 .load_flow(artifact_dir, &pipeline.flow)
@@ -48,71 +115,154 @@ pub async fn run(artifact_dir: &Path, manifest: &Manifest) -> Result<RunReport>
         }
         plans.push(PipelinePlan {
             name: pipeline.name.clone(),
-            in_format: pipeline.source.format.as_str().into(),
-            out_format: pipeline.sink.format.as_str().into(),
+            source_type: pipeline.source.connector().into(),
+            in_format: pipeline.source.format().into(),
+            out_format: pipeline.sink.format().into(),
+            rate_limit: pipeline.rate_limit,
             source,
+            pulled,
             sink,
             flow: Arc::clone(&flows[&pipeline.flow]),
         });
     }
 
+    let mut report = run_plans(plans, options).await;
+    report.throughput = counters
+        .into_iter()
+        .map(|(name, pulled, pushed)| {
+            let traffic = Traffic {
+                pulled: pulled.snapshot(),
+                pushed: pushed.snapshot(),
+            };
+            (name, traffic)
+        })
+        .collect();
+    Ok(report)
+}
+
+/// Run built pipelines concurrently and collect their outcomes.
+async fn run_plans(plans: Vec<PipelinePlan>, options: &Options) -> RunReport {
     // Spawn one task per pipeline; tasks own their connectors and share the
     // flow module behind an Arc. The task id → name map lets a panicking
     // pipeline be recorded as a failure (with its name) without aborting the
     // others — pipelines stay isolated, as on E3's scoped threads.
-    let mut set: JoinSet<Result<usize>> = JoinSet::new();
-    let mut names: HashMap<tokio::task::Id, String> = HashMap::new();
-    for plan in plans {
+    let mut set: JoinSet<Result<Tally>> = JoinSet::new();
+    let mut names: HashMap<tokio::task::Id, (usize, String)> = HashMap::new();
+    for (index, plan) in plans.into_iter().enumerate() {
         let name = plan.name.clone();
-        let handle = set.spawn(run_pipeline(plan));
-        names.insert(handle.id(), name);
+        let handle = set.spawn(run_pipeline(plan, options.clone()));
+        names.insert(handle.id(), (index, name));
     }
 
+    let mut completed = Vec::new();
     let mut failures = Vec::new();
     let mut documents = 0;
+    let mut skipped = 0;
     while let Some(joined) = set.join_next_with_id().await {
         match joined {
-            Ok((_, Ok(count))) => documents += count,
-            Ok((id, Err(err))) => failures.push((names[&id].clone(), format!("{err:#}"))),
+            Ok((id, Ok(tally))) => {
+                documents += tally.documents;
+                skipped += tally.skipped;
+                let (index, name) = &names[&id];
+                completed.push((*index, name.clone(), tally));
+            }
+            Ok((id, Err(err))) => failures.push((names[&id].1.clone(), format!("{err:#}"))),
             Err(join_err) => {
-                let name = names.get(&join_err.id()).cloned().unwrap_or_default();
+                let name = names.get(&join_err.id()).cloned().unwrap_or_default().1;
                 failures.push((name, "pipeline task panicked".into()));
             }
         }
     }
-    Ok(RunReport {
+    // Tasks finish in any order; report completions in declaration order.
+    completed.sort_by_key(|(index, _, _)| *index);
+    RunReport {
+        completed: completed
+            .into_iter()
+            .map(|(_, name, tally)| (name, tally))
+            .collect(),
         failures,
         documents,
-    })
+        skipped,
+        throughput: Vec::new(),
+    }
 }
 
-/// Everything one pipeline task owns: its name and the source/sink formats
-/// (the only manifest fields the loop needs), its built connectors, and a
-/// handle to the shared flow module. The formats are `Arc<str>` so each
-/// document's `spawn_blocking` clone is one atomic bump, not a fresh alloc.
+/// Everything one pipeline task owns: its name, source type, source/sink
+/// formats, and rate limit (the only manifest fields the loop needs), its
+/// built connectors, its source's counters (for pull timeouts), and a handle
+/// to the shared flow transform. The formats
+/// are `Arc<str>` so each document's `spawn_blocking` clone is one atomic
+/// bump, not a fresh alloc.
 struct PipelinePlan {
     name: String,
+    source_type: String,
     in_format: Arc<str>,
     out_format: Arc<str>,
+    rate_limit: Option<NonZeroU32>,
     source: Box<dyn Source>,
+    pulled: Arc<Throughput>,
     sink: Box<dyn Sink>,
-    flow: Arc<FlowModule>,
+    flow: Arc<dyn Transform>,
 }
 
 /// One pipeline: pull each document from the source in order, run it through
-/// the flow, write the result to the sink. Returns the document count.
-async fn run_pipeline(plan: PipelinePlan) -> Result<usize> {
+/// the flow, write the result to the sink. Returns what moved.
+async fn run_pipeline(plan: PipelinePlan, options: Options) -> Result<Tally> {
     let PipelinePlan {
         name,
+        source_type,
         in_format,
         out_format,
+        rate_limit,
         mut source,
+        pulled,
         mut sink,
         flow,
     } = plan;
 
+    let deadline = options
+        .max_runtime
+        .map(|limit| tokio::time::Instant::now() + limit);
     let mut documents = 0;
-    while let Some(doc) = source.next().await? {
+    let mut skipped = 0;
+    let mut drained = false;
+    let mut limit = rate_limit.map(RateLimit::per_second);
+    loop {
+        // A graceful drain: the deadline can cut short a wait on the rate
+        // limit, which holds no document, but never a pull. A pull the source
+        // has started runs to completion (bounded by `--pull-timeout-ms`), as
+        // in `pull`: dropping it could lose a document already dequeued.
+        let turn = async {
+            if let Some(limit) = limit.as_mut() {
+                limit.acquire().await;
+            }
+        };
+        let in_time = match deadline {
+            Some(deadline) if tokio::time::Instant::now() >= deadline => false,
+            Some(deadline) => tokio::time::timeout_at(deadline, turn).await.is_ok(),
+            None => {
+                turn.await;
+                true
+            }
+        };
+        if !in_time {
+            drained = true;
+            log::max_runtime(&name, documents);
+            break;
+        }
+        let timeout = options.pull_timeout;
+        let next = pull(source.as_mut(), &pulled, &name, &source_type, timeout).await;
+        // The source refuses an oversized document before reading it, so one
+        // pathological document fails loudly instead of ballooning memory.
+        if let Err(err) = &next
+            && let Some(refused) = err.downcast_ref::<MessageTooLarge>()
+        {
+            let document = documents + 1;
+            let message = refused.to_string();
+            log::error(&name, document, "source", "MessageTooLarge", &message);
+            bail!("document {document}: source: {message}");
+        }
+        let Some(doc) = next? else { break };
         documents += 1;
 
         // The transform is synchronous and CPU-bound; run it off the async
@@ -122,16 +272,26 @@ async fn run_pipeline(plan: PipelinePlan) -> Result<usize> {
             let in_format = Arc::clone(&in_format);
             let out_format = Arc::clone(&out_format);
             let payload = doc.payload;
-            tokio::task::spawn_blocking(move || {
+            let joined = tokio::task::spawn_blocking(move || {
                 flow.run(&InputEnvelope {
                     r#in: &in_format,
                     out: &out_format,
                     payload: &payload,
                 })
             })
-            .await
-            .context("transform task panicked")?
-            .with_context(|| format!("document {documents} ({})", doc.origin))?
+            .await;
+            // A panic inside the transform stays inside its blocking task;
+            // report it like any other failed document, with what it said.
+            let run = match joined {
+                Ok(run) => run,
+                Err(err) if err.is_panic() => {
+                    let message = panic_message(err.into_panic());
+                    log::error(&name, documents, "transform", "Panic", &message);
+                    bail!("document {documents}: transform: panicked: {message}");
+                }
+                Err(err) => return Err(err).context("transform task was cancelled"),
+            };
+            run.with_context(|| format!("document {documents} ({})", doc.origin))?
         };
 
         if !result.ok {
@@ -144,16 +304,429 @@ async fn run_pipeline(plan: PipelinePlan) -> Result<usize> {
                 .and_then(|e| e.message.as_deref())
                 .unwrap_or("(no message)");
             log::error(&name, documents, stage, error_type, message);
-            // Every source this phase is bounded (files), so a poison document
-            // fails the run. A live stream would log-and-move-on here instead.
+            // Every source this phase is bounded (files, sqlite tables), so a
+            // poison document fails the run. A live stream would
+            // log-and-move-on here instead.
             bail!("document {documents}: {stage}: {message}");
         }
 
-        let output = result
-            .payload
-            .context("ok envelope is missing its payload")?;
-        sink.write(&output).await?;
-        log::done(&name, documents);
+        if result.skipped {
+            skipped += 1;
+            log::skipped_empty(&name, documents);
+        } else {
+            let output = result
+                .payload
+                .context("ok envelope is missing its payload")?;
+            sink.write(&output).await?;
+            log::done(&name, documents);
+        }
+
+        if options.once_per_pipeline {
+            break;
+        }
+    }
+    Ok(Tally {
+        documents,
+        skipped,
+        drained,
+        throttled: limit.map(|limit| limit.throttle()),
+    })
+}
+
+/// The text a panic was raised with: `panic!` carries a `&str` or a `String`.
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(|| "(no message)".to_string(), |message| message.to_string()),
+    }
+}
+
+/// Pull the next document. With a deadline set, each elapsed deadline logs a
+/// warning, counts a timeout in the source's `counts`, and keeps waiting on the *same* pull — dropping it could lose a
+/// document the source has already dequeued — until `PULL_STRIKES` misses in
+/// a row fail the pipeline as stalled.
+async fn pull(
+    source: &mut dyn Source,
+    counts: &Throughput,
+    pipeline: &str,
+    connector: &str,
+    timeout: Option<Duration>,
+) -> Result<Option<SourceDoc>> {
+    let Some(timeout) = timeout else {
+        return source.next().await;
+    };
+    let mut next = source.next();
+    for strike in 1..=PULL_STRIKES {
+        match tokio::time::timeout(timeout, &mut next).await {
+            Ok(result) => return result,
+            Err(_) => {
+                counts.record_timeout();
+                log::pull_timeout(pipeline, connector, strike, timeout);
+            }
+        }
+    }
+    bail!(
+        "{connector} source stalled: no document within {} ms, {PULL_STRIKES} times in a row",
+        timeout.as_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connector::SourceDoc;
+    use crate::host::ResultEnvelope;
+    use async_trait::async_trait;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// An in-memory source yielding the given payloads in order.
+    struct VecSource(VecDeque<String>);
+
+    #[async_trait]
+    impl Source for VecSource {
+        async fn next(&mut self) -> Result<Option<SourceDoc>> {
+            Ok(self.0.pop_front().map(|payload| SourceDoc {
+                origin: "mem".into(),
+                payload,
+            }))
+        }
+    }
+
+    /// An in-memory sink recording every write, shared with the test body.
+    struct VecSink(Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl Sink for VecSink {
+        async fn write(&mut self, payload: &str) -> Result<()> {
+            self.0.lock().unwrap().push(payload.to_string());
+            Ok(())
+        }
+    }
+
+    /// A transform that returns its input payload unchanged.
+    struct Echo;
+
+    impl Transform for Echo {
+        fn run(&self, input: &InputEnvelope<'_>) -> Result<ResultEnvelope> {
+            Ok(ResultEnvelope {
+                ok: true,
+                payload: Some(input.payload.to_string()),
+                error: None,
+                skipped: false,
+            })
+        }
+    }
+
+    /// Echo, except an empty object comes back skipped (a flow under
+    /// `onEmptyOutput: skip` that emptied the document).
+    struct SkipEmpty;
+
+    impl Transform for SkipEmpty {
+        fn run(&self, input: &InputEnvelope<'_>) -> Result<ResultEnvelope> {
+            let skipped = input.payload == "{}";
+            Ok(ResultEnvelope {
+                ok: true,
+                payload: (!skipped).then(|| input.payload.to_string()),
+                error: None,
+                skipped,
+            })
+        }
+    }
+
+    /// Echo, except a document reading "boom" panics mid-transform.
+    struct PanicsOnBoom;
+
+    impl Transform for PanicsOnBoom {
+        fn run(&self, input: &InputEnvelope<'_>) -> Result<ResultEnvelope> {
+            if input.payload == "boom" {
+                panic!("index out of bounds: the len is 0 but the index is 0");
+            }
+            Echo.run(input)
+        }
+    }
+
+    fn plan(payloads: &[&str]) -> (PipelinePlan, Arc<Mutex<Vec<String>>>) {
+        named_plan("test", payloads)
+    }
+
+    fn named_plan(name: &str, payloads: &[&str]) -> (PipelinePlan, Arc<Mutex<Vec<String>>>) {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let plan = PipelinePlan {
+            name: name.into(),
+            source_type: "mem".into(),
+            in_format: "json".into(),
+            out_format: "json".into(),
+            rate_limit: None,
+            source: Box::new(VecSource(payloads.iter().map(|p| p.to_string()).collect())),
+            pulled: Arc::default(),
+            sink: Box::new(VecSink(Arc::clone(&written))),
+            flow: Arc::new(Echo),
+        };
+        (plan, written)
+    }
+
+    /// Drive an async test body on a fresh runtime (no `#[tokio::test]` — the
+    /// `macros` feature is off). Multi-threaded so `spawn_blocking` runs.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Runtime::new().unwrap().block_on(future)
+    }
+
+    #[test]
+    fn every_document_reaches_the_sink_in_order() {
+        let (plan, written) = plan(&["a", "b", "c"]);
+        let tally = block_on(run_pipeline(plan, Options::default())).unwrap();
+        assert_eq!(tally.documents, 3);
+        assert_eq!(*written.lock().unwrap(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn a_skipped_empty_output_is_counted_and_not_written() {
+        let (mut plan, written) = plan(&["{\"a\":1}", "{}", "{\"b\":2}"]);
+        plan.flow = Arc::new(SkipEmpty);
+        let report = block_on(run_plans(vec![plan], &Options::default()));
+        let tally = Tally {
+            documents: 3,
+            skipped: 1,
+            drained: false,
+            throttled: None,
+        };
+        assert_eq!(report.completed, [("test".to_string(), tally)]);
+        assert_eq!((report.documents, report.skipped), (3, 1));
+        assert_eq!(*written.lock().unwrap(), ["{\"a\":1}", "{\"b\":2}"]);
+    }
+
+    #[test]
+    fn a_panicking_transform_fails_its_document_and_spares_other_pipelines() {
+        let (mut orders, orders_out) = named_plan("orders", &["o1", "boom", "o3"]);
+        orders.flow = Arc::new(PanicsOnBoom);
+        let (refunds, refunds_out) = named_plan("refunds", &["r1", "r2"]);
+        let report = block_on(run_plans(vec![orders, refunds], &Options::default()));
+
+        let [(failed, err)] = &report.failures[..] else {
+            panic!("expected one failure, got {:?}", report.failures);
+        };
+        assert_eq!(failed, "orders");
+        assert_eq!(
+            err,
+            "document 2: transform: panicked: index out of bounds: the len is 0 but the index is 0"
+        );
+        assert_eq!(*orders_out.lock().unwrap(), ["o1"]);
+        assert_eq!(report.completed.len(), 1);
+        assert_eq!(*refunds_out.lock().unwrap(), ["r1", "r2"]);
+    }
+
+    /// A source whose second document is over an 8-byte limit.
+    struct OversizedSecond(usize);
+
+    #[async_trait]
+    impl Source for OversizedSecond {
+        async fn next(&mut self) -> Result<Option<SourceDoc>> {
+            self.0 += 1;
+            let origin = format!("mem:{}", self.0);
+            if self.0 == 2 {
+                MessageTooLarge::check(Some(8), 19, || origin.clone())?;
+            }
+            Ok(Some(SourceDoc {
+                origin,
+                payload: "{}".into(),
+            }))
+        }
+    }
+
+    #[test]
+    fn an_oversized_document_is_refused_after_smaller_ones_pass() {
+        let (mut plan, written) = plan(&[]);
+        plan.source = Box::new(OversizedSecond(0));
+        let err = block_on(run_pipeline(plan, Options::default()))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "document 2: source: mem:2 is 19 bytes, over the 8-byte --max-message-bytes limit"
+        );
+        // The first document passed; nothing after the oversized one moved.
+        assert_eq!(*written.lock().unwrap(), ["{}"]);
+    }
+
+    #[test]
+    fn once_per_pipeline_moves_exactly_one_document_through_each_pipeline() {
+        let (orders, orders_out) = named_plan("orders", &["o1", "o2", "o3"]);
+        let (refunds, refunds_out) = named_plan("refunds", &["r1", "r2"]);
+        let options = Options {
+            once_per_pipeline: true,
+            ..Options::default()
+        };
+        let report = block_on(run_plans(vec![orders, refunds], &options));
+        assert!(report.failures.is_empty());
+        let one = Tally {
+            documents: 1,
+            skipped: 0,
+            drained: false,
+            throttled: None,
+        };
+        assert_eq!(
+            report.completed,
+            [("orders".to_string(), one), ("refunds".to_string(), one)]
+        );
+        assert_eq!(report.documents, 2);
+        assert_eq!(*orders_out.lock().unwrap(), ["o1"]);
+        assert_eq!(*refunds_out.lock().unwrap(), ["r1"]);
+    }
+
+    /// A source whose first pull takes `delay` and whose second never resolves.
+    struct SlowSource {
+        delay: Duration,
+        yielded: bool,
+    }
+
+    #[async_trait]
+    impl Source for SlowSource {
+        async fn next(&mut self) -> Result<Option<SourceDoc>> {
+            if self.yielded {
+                std::future::pending::<()>().await;
+            }
+            tokio::time::sleep(self.delay).await;
+            self.yielded = true;
+            Ok(Some(SourceDoc {
+                origin: "slow".into(),
+                payload: "late".into(),
+            }))
+        }
+    }
+
+    /// A source that never runs dry, yielding a document every `every`.
+    struct Ticker {
+        every: Duration,
+    }
+
+    #[async_trait]
+    impl Source for Ticker {
+        async fn next(&mut self) -> Result<Option<SourceDoc>> {
+            tokio::time::sleep(self.every).await;
+            Ok(Some(SourceDoc {
+                origin: "tick".into(),
+                payload: "{}".into(),
+            }))
+        }
+    }
+
+    #[test]
+    fn max_runtime_drains_an_endless_source_and_succeeds() {
+        let (mut plan, written) = plan(&[]);
+        plan.source = Box::new(Ticker {
+            every: Duration::from_millis(20),
+        });
+        let options = Options {
+            max_runtime: Some(Duration::from_millis(150)),
+            ..Options::default()
+        };
+        let started = std::time::Instant::now();
+        let tally = block_on(run_pipeline(plan, options)).unwrap();
+        let elapsed = started.elapsed();
+
+        assert!(tally.drained);
+        assert!(tally.documents > 0, "{tally:?}");
+        // Every document pulled before the deadline was written.
+        assert_eq!(written.lock().unwrap().len(), tally.documents);
+        assert!(elapsed >= Duration::from_millis(150), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(2), "{elapsed:?}");
+    }
+
+    #[test]
+    fn a_rate_limited_pipeline_moves_no_faster_than_its_limit() {
+        let (mut plan, written) = plan(&["a", "b", "c", "d", "e"]);
+        plan.rate_limit = NonZeroU32::new(20);
+        let started = std::time::Instant::now();
+        let tally = block_on(run_pipeline(plan, Options::default())).unwrap();
+        let elapsed = started.elapsed();
+
+        assert_eq!(written.lock().unwrap().len(), 5);
+        // The first document goes at once; each of the other four waits its
+        // 50 ms turn, so five documents at 20/s take at least 200 ms.
+        assert!(elapsed >= Duration::from_millis(200), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(2), "{elapsed:?}");
+        let throttle = tally
+            .throttled
+            .expect("a limited pipeline reports its limit");
+        assert_eq!(throttle.limit.get(), 20);
+        assert!(
+            throttle.waited >= Duration::from_millis(150),
+            "{throttle:?}"
+        );
+        // Five documents over the four 50 ms gaps between them.
+        let achieved = throttle.achieved(tally.documents);
+        assert!(achieved > 0.0 && achieved <= 25.0, "{achieved}");
+    }
+
+    #[test]
+    fn max_runtime_lets_a_pull_under_way_deliver_its_document() {
+        let (mut plan, written) = plan(&[]);
+        plan.source = Box::new(SlowSource {
+            delay: Duration::from_millis(80),
+            yielded: false,
+        });
+        let options = Options {
+            max_runtime: Some(Duration::from_millis(20)),
+            ..Options::default()
+        };
+        let tally = block_on(run_pipeline(plan, options)).unwrap();
+        // The first pull outlasts the deadline and is still written; the
+        // deadline then stops the pipeline before its endless second pull.
+        assert_eq!((tally.documents, tally.drained), (1, true));
+        assert_eq!(*written.lock().unwrap(), ["late"]);
+    }
+
+    #[test]
+    fn max_runtime_leaves_a_bounded_run_that_finishes_in_time_alone() {
+        let (plan, _) = plan(&["a", "b"]);
+        let options = Options {
+            max_runtime: Some(Duration::from_secs(60)),
+            ..Options::default()
+        };
+        let tally = block_on(run_pipeline(plan, options)).unwrap();
+        assert_eq!((tally.documents, tally.drained), (2, false));
+    }
+
+    #[test]
+    fn a_slow_pull_warns_but_keeps_its_document() {
+        block_on(async {
+            let mut source = SlowSource {
+                delay: Duration::from_millis(30),
+                yielded: false,
+            };
+            // The 30 ms pull misses one 20 ms deadline and lands inside the
+            // next; the same pull is awaited throughout, so nothing is lost.
+            let counts = Throughput::default();
+            let timeout = Some(Duration::from_millis(20));
+            let doc = pull(&mut source, &counts, "p", "slow", timeout)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(doc.payload, "late");
+            assert_eq!(counts.snapshot().timeouts, 1);
+        });
+    }
+
+    #[test]
+    fn a_pull_that_never_resolves_fails_after_repeated_deadlines() {
+        block_on(async {
+            let mut source = SlowSource {
+                delay: Duration::ZERO,
+                yielded: true,
+            };
+            let counts = Throughput::default();
+            let timeout = Some(Duration::from_millis(5));
+            let err = pull(&mut source, &counts, "p", "slow", timeout)
+                .await
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(err.contains("slow source stalled"), "{err}");
+            assert!(err.contains("3 times in a row"), "{err}");
+            assert_eq!(counts.snapshot().timeouts, 3);
+        });
     }
-    Ok(documents)
 }
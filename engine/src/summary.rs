@@ -0,0 +1,208 @@
+//! The end-of-run summary: one entry per pipeline with what it moved and how
+//! it ended, so CI can read a run's outcome from `--output json` instead of
+//! scraping the human status lines.
+
+use crate::metrics::Counts;
+use crate::runner::RunReport;
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Summary {
+    /// Every pipeline ran; the engine exits 0 exactly when this is true.
+    pub ok: bool,
+    /// One entry per pipeline, in manifest declaration order.
+    pub pipelines: Vec<PipelineSummary>,
+    pub documents: usize,
+    pub skipped: usize,
+    /// Wall-clock time from boot to the end of the last pipeline.
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PipelineSummary {
+    pub name: String,
+    pub status: Status,
+    /// What the source yielded (and how many pull deadlines it missed) and
+    /// what the sink wrote.
+    pub pulled: Counts,
+    pub pushed: Counts,
+    /// Documents dropped as empty output; only known for a pipeline that finished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<usize>,
+    /// The pipeline's `rateLimit`, the documents per second it actually moved
+    /// (over its whole run, to two decimals), and the time it spent waiting on
+    /// the limit; only for a limited pipeline that finished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub achieved_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throttled_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// The source ran dry and every document was handled.
+    Ok,
+    /// `--max-runtime` stopped the pipeline; what it pulled was handled.
+    Drained,
+    Failed,
+}
+
+impl Summary {
+    pub fn new(report: &RunReport, elapsed: Duration) -> Self {
+        // Throughput lists every pipeline, failed ones included, in order.
+        let pipelines = report
+            .throughput
+            .iter()
+            .map(|(name, traffic)| {
+                let tally = report.completed.iter().find(|(n, _)| n == name);
+                let error = report.failures.iter().find(|(n, _)| n == name);
+                let status = match (tally, error) {
+                    (_, Some(_)) => Status::Failed,
+                    (Some((_, tally)), None) if tally.drained => Status::Drained,
+                    _ => Status::Ok,
+                };
+                let throttle =
+                    tally.and_then(|(_, tally)| Some((tally.documents, tally.throttled?)));
+                PipelineSummary {
+                    name: name.clone(),
+                    status,
+                    pulled: traffic.pulled,
+                    pushed: traffic.pushed,
+                    skipped: tally.map(|(_, tally)| tally.skipped),
+                    rate_limit: throttle.map(|(_, throttle)| throttle.limit.get()),
+                    achieved_rate: throttle.map(|(documents, throttle)| {
+                        (throttle.achieved(documents) * 100.0).round() / 100.0
+                    }),
+                    throttled_ms: throttle.map(|(_, throttle)| throttle.waited.as_millis() as u64),
+                    error: error.map(|(_, message)| message.clone()),
+                }
+            })
+            .collect();
+        Summary {
+            ok: report.failures.is_empty(),
+            pipelines,
+            documents: report.documents,
+            skipped: report.skipped,
+            elapsed_ms: elapsed.as_millis() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{Tally, Traffic};
+    use crate::throttle::Throttle;
+    use serde_json::json;
+    use std::num::NonZeroU32;
+
+    fn counts(messages: u64, bytes: u64) -> Counts {
+        Counts {
+            messages,
+            bytes,
+            timeouts: 0,
+        }
+    }
+
+    #[test]
+    fn one_entry_per_pipeline_with_its_outcome() {
+        let report = RunReport {
+            completed: vec![
+                (
+                    "orders".into(),
+                    Tally {
+                        documents: 3,
+                        skipped: 1,
+                        drained: false,
+                        throttled: Some(Throttle {
+                            limit: NonZeroU32::new(2).unwrap(),
+                            waited: Duration::from_millis(1500),
+                            elapsed: Duration::from_millis(1600),
+                        }),
+                    },
+                ),
+                (
+                    "ticks".into(),
+                    Tally {
+                        documents: 2,
+                        skipped: 0,
+                        drained: true,
+                        throttled: None,
+                    },
+                ),
+            ],
+            failures: vec![("refunds".into(), "document 2: transform: bad".into())],
+            documents: 5,
+            skipped: 1,
+            throughput: vec![
+                (
+                    "orders".into(),
+                    Traffic {
+                        pulled: counts(3, 30),
+                        pushed: counts(2, 40),
+                    },
+                ),
+                (
+                    "refunds".into(),
+                    Traffic {
+                        pulled: Counts {
+                            timeouts: 2,
+                            ..counts(2, 8)
+                        },
+                        pushed: counts(1, 4),
+                    },
+                ),
+                (
+                    "ticks".into(),
+                    Traffic {
+                        pulled: counts(2, 4),
+                        pushed: counts(2, 4),
+                    },
+                ),
+            ],
+        };
+
+        let summary = Summary::new(&report, Duration::from_millis(1250));
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            json!({
+                "ok": false,
+                "pipelines": [
+                    {
+                        "name": "orders",
+                        "status": "ok",
+                        "pulled": { "messages": 3, "bytes": 30 },
+                        "pushed": { "messages": 2, "bytes": 40 },
+                        "skipped": 1,
+                        "rate_limit": 2,
+                        "achieved_rate": 1.88,
+                        "throttled_ms": 1500
+                    },
+                    {
+                        "name": "refunds",
+                        "status": "failed",
+                        "pulled": { "messages": 2, "bytes": 8, "timeouts": 2 },
+                        "pushed": { "messages": 1, "bytes": 4 },
+                        "error": "document 2: transform: bad"
+                    },
+                    {
+                        "name": "ticks",
+                        "status": "drained",
+                        "pulled": { "messages": 2, "bytes": 4 },
+                        "pushed": { "messages": 2, "bytes": 4 },
+                        "skipped": 0
+                    }
+                ],
+                "documents": 5,
+                "skipped": 1,
+                "elapsed_ms": 1250
+            })
+        );
+    }
+}
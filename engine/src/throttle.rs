@@ -0,0 +1,79 @@
+//! Per-pipeline rate limiting (`rateLimit` in the manifest). The run loop
+//! takes a token before each pull, so a pipeline that feeds a fragile
+//! downstream never moves documents faster than its limit.
+
+use std::num::NonZeroU32;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// A token bucket that holds one token and refills at `rate` per second.
+/// With room for only one token there are no bursts: pulls are spaced at
+/// least `1 / rate` apart, so the limit holds over any window, not just on
+/// average.
+#[derive(Debug)]
+pub struct RateLimit {
+    rate: NonZeroU32,
+    interval: Duration,
+    /// When the limit was set up, i.e. when the pipeline started pulling.
+    started: Instant,
+    /// When the next token is available.
+    next: Instant,
+    /// Total time spent waiting for a token.
+    waited: Duration,
+}
+
+impl RateLimit {
+    pub fn per_second(rate: NonZeroU32) -> Self {
+        let now = Instant::now();
+        Self {
+            rate,
+            interval: Duration::from_secs(1) / rate.get(),
+            started: now,
+            next: now,
+            waited: Duration::ZERO,
+        }
+    }
+
+    /// Wait until a token is available, then take it.
+    pub async fn acquire(&mut self) {
+        let now = Instant::now();
+        if self.next > now {
+            tokio::time::sleep_until(self.next).await;
+            self.waited += self.next - now;
+        }
+        self.next = self.next.max(now) + self.interval;
+    }
+
+    /// What the limit did so far: the configured rate, the total wait, and
+    /// how long the pipeline has been running under it.
+    pub fn throttle(&self) -> Throttle {
+        Throttle {
+            limit: self.rate,
+            waited: self.waited,
+            elapsed: self.started.elapsed(),
+        }
+    }
+}
+
+/// A rate-limited pipeline's end-of-run view of its limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throttle {
+    /// The configured `rateLimit`, in documents per second.
+    pub limit: NonZeroU32,
+    /// Total time spent waiting for a token.
+    pub waited: Duration,
+    /// Time from the first pull to the end of the pipeline.
+    pub elapsed: Duration,
+}
+
+impl Throttle {
+    /// Documents per second the pipeline actually moved.
+    pub fn achieved(&self, documents: usize) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            documents as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
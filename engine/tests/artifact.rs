@@ -52,7 +52,7 @@ fn stage(name: &str, artifact: &Path, glob: &str, inputs: &[(&str, &str)]) -> Pa
     dir
 }
 
-fn run_engine(artifact_dir: &Path) -> Output {
+fn run_engine(artifact_dir: &Path, args: &[&str]) -> Output {
     // Boot from a weavster.yaml beside the staged artifact; the engine resolves
     // documents/sink paths against the --artifact dir.
     let config = artifact_dir.join("weavster.yaml");
@@ -66,6 +66,7 @@ fn run_engine(artifact_dir: &Path) -> Output {
         .arg(&config)
         .arg("--artifact")
         .arg(artifact_dir)
+        .args(args)
         .output()
         .expect("run the weavster-engine binary")
 }
@@ -82,7 +83,7 @@ fn runs_the_golden_pipeline_end_to_end() {
         &[("order.json", ORDER_DOC)],
     );
 
-    let output = run_engine(&dir);
+    let output = run_engine(&dir, &[]);
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(output.status.success(), "{stderr}");
 
@@ -113,7 +114,7 @@ fn processes_glob_matches_in_input_order_with_structured_logs() {
         ],
     );
 
-    let output = run_engine(&dir);
+    let output = run_engine(&dir, &[]);
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(output.status.success(), "{stderr}");
     assert!(
@@ -150,7 +151,7 @@ fn a_poison_document_fails_the_bounded_run_with_stage() {
         &[("a.json", ORDER_DOC), ("b.json", "{ not json")],
     );
 
-    let output = run_engine(&dir);
+    let output = run_engine(&dir, &[]);
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(!output.status.success());
     // The failure is scoped: pipeline + document + stage, in the structured log.
@@ -165,3 +166,38 @@ fn a_poison_document_fails_the_bounded_run_with_stage() {
 
     fs::remove_dir_all(&dir).ok();
 }
+
+#[test]
+fn quiet_json_output_summarizes_a_failed_run_for_ci() {
+    let Some(artifact) = golden_artifact() else {
+        return;
+    };
+    let dir = stage(
+        "summary",
+        &artifact,
+        "in/*.json",
+        &[("a.json", ORDER_DOC), ("b.json", "{ not json")],
+    );
+
+    let output = run_engine(&dir, &["--quiet", "--output", "json"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["ok"], false);
+    let pipeline = &summary["pipelines"][0];
+    assert_eq!(pipeline["name"], "order");
+    assert_eq!(pipeline["status"], "failed");
+    assert_eq!(pipeline["pulled"]["messages"], 2);
+    assert_eq!(pipeline["pushed"]["messages"], 1);
+
+    // Quiet keeps the error line and drops the info ones.
+    let levels: Vec<String> = stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .map(|v| v["level"].as_str().unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(levels, ["error"], "{stderr}");
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -139,16 +139,18 @@ fn missing_flow_module_fails_at_startup() {
 
 #[test]
 fn unknown_connector_type_fails_with_a_clear_error() {
-    // The manifest is shape-valid; the registry rejects the connector type.
+    // Loading the manifest rejects the connector type, naming the ones that
+    // exist, so no .wasm is needed — the unknown type aborts startup first.
     let manifest =
         GOLDEN_HEAD.replace(r#"{ "type": "file", "glob""#, r#"{ "type": "rest", "glob""#);
     let dir = temp_artifact("badtype", &manifest);
-    // Connectors are built before flow modules load, so no .wasm is needed —
-    // the unknown type aborts startup first.
     let output = run_engine(&dir);
     fs::remove_dir_all(&dir).ok();
 
     assert!(!output.status.success());
     let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("unknown source type \"rest\""), "{stderr}");
+    assert!(
+        stderr.contains("unknown variant `rest`, expected `file` or `sqlite`"),
+        "{stderr}"
+    );
 }